@@ -0,0 +1,239 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Error, Fields, Lifetime,
+    LitStr, Result, Type,
+};
+
+/// Derives `aoc_helpers::parse::Parse` (and `ParseRef`) from a format string.
+///
+/// Structs take `#[aoc(format = "{name} can fly {speed} km/s")]` where every
+/// placeholder names a field (`{0}`, `{1}`, ... for tuple structs). Fields are
+/// parsed with their own type unless `#[aoc(with = VecFromCommaSeparated<usize>)]`
+/// selects another combinator. Enums take a format per variant and try them in order.
+#[proc_macro_derive(Parse, attributes(aoc))]
+pub fn derive_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct AocAttrs {
+    format: Option<LitStr>,
+    with: Option<Type>,
+}
+
+fn aoc_attrs(attrs: &[Attribute]) -> Result<AocAttrs> {
+    let mut result = AocAttrs::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("format") {
+                result.format = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("with") {
+                result.with = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `format` or `with`"))
+            }
+        })?;
+    }
+    Ok(result)
+}
+
+/// Format split into literals with a placeholder between each consecutive pair.
+struct Format {
+    literals: Vec<String>,
+    placeholders: Vec<String>,
+}
+
+fn parse_format(format: &LitStr) -> Result<Format> {
+    let value = format.value();
+    let mut literals = vec![String::new()];
+    let mut placeholders = Vec::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literals.last_mut().unwrap().push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literals.last_mut().unwrap().push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(Error::new(format.span(), "unclosed `{` in format")),
+                    }
+                }
+                let name = name.trim().to_owned();
+                if name.is_empty() {
+                    return Err(Error::new(format.span(), "empty placeholder in format"));
+                }
+                if !placeholders.is_empty() && literals.last().unwrap().is_empty() {
+                    return Err(Error::new(
+                        format.span(),
+                        "placeholders must be separated by literal text",
+                    ));
+                }
+                placeholders.push(name);
+                literals.push(String::new());
+            }
+            '}' => return Err(Error::new(format.span(), "unmatched `}` in format")),
+            c => literals.last_mut().unwrap().push(c),
+        }
+    }
+    Ok(Format {
+        literals,
+        placeholders,
+    })
+}
+
+/// Builds an expression evaluating to `Result<Self>` for `path { fields }`.
+fn construct(path: TokenStream2, fields: &Fields, format: &LitStr) -> Result<TokenStream2> {
+    let Format {
+        literals,
+        placeholders,
+    } = parse_format(format)?;
+    let names = match fields {
+        Fields::Named(named) => named
+            .named
+            .iter()
+            .map(|field| field.ident.as_ref().unwrap().to_string())
+            .collect::<Vec<_>>(),
+        Fields::Unnamed(unnamed) => (0..unnamed.unnamed.len())
+            .map(|idx| idx.to_string())
+            .collect(),
+        Fields::Unit => Vec::new(),
+    };
+    for placeholder in &placeholders {
+        if !names.contains(placeholder) {
+            return Err(Error::new(
+                format.span(),
+                format!("unknown field `{}` in format", placeholder),
+            ));
+        }
+    }
+    let mut values = Vec::new();
+    for (field, name) in fields.iter().zip(&names) {
+        let mut positions = placeholders.iter().enumerate().filter(|(_, p)| *p == name);
+        let idx = match (positions.next(), positions.next()) {
+            (Some((idx, _)), None) => idx,
+            (None, _) => {
+                return Err(Error::new(
+                    field.span(),
+                    format!("field `{}` is missing from the format", name),
+                ))
+            }
+            (Some(_), Some(_)) => {
+                return Err(Error::new(
+                    format.span(),
+                    format!("field `{}` appears more than once in the format", name),
+                ))
+            }
+        };
+        let ty = aoc_attrs(&field.attrs)?
+            .with
+            .unwrap_or_else(|| field.ty.clone());
+        let value = quote! {
+            <#ty as ::aoc_helpers::parse::Parse>::parse(values[#idx]).map_err(|e| {
+                ::aoc_helpers::anyhow::anyhow!("Failed to parse `{}`: {}", #name, e)
+            })?
+        };
+        values.push(match &field.ident {
+            Some(ident) => quote! { #ident: #value },
+            None => value,
+        });
+    }
+    let fields = match fields {
+        Fields::Named(_) => quote! { { #(#values),* } },
+        Fields::Unnamed(_) => quote! { ( #(#values),* ) },
+        Fields::Unit => quote! {},
+    };
+    Ok(quote! {
+        (|| -> ::aoc_helpers::anyhow::Result<Self> {
+            let values = ::aoc_helpers::parse::match_format(raw_input, &[#(#literals),*])?;
+            let _ = &values;
+            Ok(#path #fields)
+        })()
+    })
+}
+
+fn required_format(attrs: &[Attribute], span: proc_macro2::Span) -> Result<LitStr> {
+    aoc_attrs(attrs)?
+        .format
+        .ok_or_else(|| Error::new(span, "missing `#[aoc(format = \"...\")]`"))
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "`#[derive(Parse)]` does not support generic types",
+        ));
+    }
+    let name = &input.ident;
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let format = required_format(&input.attrs, input.ident.span())?;
+            construct(quote! { Self }, &data.fields, &format)?
+        }
+        Data::Enum(data) => {
+            let attempts = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let ident = &variant.ident;
+                    let variant_name = ident.to_string();
+                    let format = required_format(&variant.attrs, ident.span())?;
+                    let attempt = construct(quote! { Self::#ident }, &variant.fields, &format)?;
+                    Ok(quote! {
+                        match #attempt {
+                            Ok(parsed) => return Ok(parsed),
+                            Err(e) => errors.push(format!("{}: {}", #variant_name, e)),
+                        }
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            quote! {{
+                let mut errors: Vec<String> = Vec::new();
+                #(#attempts)*
+                Err(::aoc_helpers::anyhow::anyhow!(
+                    "No variant matched {:?} ({})",
+                    raw_input,
+                    errors.join("; ")
+                ))
+            }}
+        }
+        Data::Union(_) => {
+            return Err(Error::new(
+                input.ident.span(),
+                "`#[derive(Parse)]` does not support unions",
+            ))
+        }
+    };
+    let lifetime = Lifetime::new("'__aoc_a", proc_macro2::Span::call_site());
+    Ok(quote! {
+        impl ::aoc_helpers::parse::Parse for #name {
+            type Parsed = Self;
+            fn parse(raw_input: &str) -> ::aoc_helpers::anyhow::Result<Self> {
+                #body
+            }
+        }
+
+        impl<#lifetime> ::aoc_helpers::parse::ParseRef<#lifetime> for #name {
+            type Parsed = Self;
+            fn parse_ref(raw_input: &#lifetime str) -> ::aoc_helpers::anyhow::Result<Self> {
+                <Self as ::aoc_helpers::parse::Parse>::parse(raw_input)
+            }
+        }
+    })
+}
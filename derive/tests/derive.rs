@@ -0,0 +1,95 @@
+use aoc_helpers::parse::{Parse, ParseRef, VecFromCommaSeparated, VecFromLines};
+
+#[derive(Debug, PartialEq, Eq, Parse)]
+#[aoc(format = "{name} can fly {speed} km/s for {time} seconds")]
+struct Reindeer {
+    name: String,
+    speed: usize,
+    time: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Parse)]
+#[aoc(format = "Valve {name} has flow rate={rate}; tunnels lead to valves {tunnels}")]
+struct Valve {
+    name: String,
+    rate: usize,
+    #[aoc(with = VecFromCommaSeparated<String>)]
+    tunnels: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Parse)]
+#[aoc(format = "<{0},{1}>")]
+struct Point(isize, isize);
+
+#[derive(Debug, PartialEq, Eq, Parse)]
+enum Instr {
+    #[aoc(format = "noop")]
+    Noop,
+    #[aoc(format = "addx {0}")]
+    Addx(isize),
+    #[aoc(format = "move {count} from {from} to {to}")]
+    Move {
+        count: usize,
+        from: usize,
+        to: usize,
+    },
+}
+
+#[test]
+fn test_struct() {
+    assert_eq!(
+        Reindeer::parse("Comet can fly 14 km/s for 10 seconds").unwrap(),
+        Reindeer {
+            name: "Comet".to_owned(),
+            speed: 14,
+            time: 10,
+        }
+    );
+    let error = Reindeer::parse("Comet can fly fast km/s for 10 seconds").unwrap_err();
+    assert!(error.to_string().contains("speed"));
+    assert!(Reindeer::parse("Comet can fly 14 km/s").is_err());
+}
+
+#[test]
+fn test_with_combinator() {
+    assert_eq!(
+        Valve::parse("Valve AA has flow rate=0; tunnels lead to valves DD, II, BB").unwrap(),
+        Valve {
+            name: "AA".to_owned(),
+            rate: 0,
+            tunnels: vec!["DD".to_owned(), "II".to_owned(), "BB".to_owned()],
+        }
+    );
+}
+
+#[test]
+fn test_tuple_struct_and_nesting() {
+    assert_eq!(
+        VecFromLines::<Point>::parse("<1,-2>\n<3,4>").unwrap(),
+        vec![Point(1, -2), Point(3, 4)]
+    );
+    assert_eq!(
+        VecFromLines::<Point>::parse_ref("<0,0>").unwrap(),
+        vec![Point(0, 0)]
+    );
+}
+
+#[test]
+fn test_enum() {
+    assert_eq!(
+        VecFromLines::<Instr>::parse("noop\naddx -5\nmove 1 from 2 to 3").unwrap(),
+        vec![
+            Instr::Noop,
+            Instr::Addx(-5),
+            Instr::Move {
+                count: 1,
+                from: 2,
+                to: 3,
+            },
+        ]
+    );
+    let error = Instr::parse("jump 3").unwrap_err().to_string();
+    assert!(error.contains("Noop"));
+    assert!(error.contains("Addx"));
+    assert!(error.contains("Move"));
+}
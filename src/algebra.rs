@@ -0,0 +1,270 @@
+use crate::{grid::Grid, matrix::Matrix};
+use anyhow::{anyhow, Result};
+use std::ops::{Add, Mul};
+
+/// Integer element type usable in matrix arithmetic.
+pub trait Number: Copy + PartialEq + Add<Output = Self> + Mul<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn rem_euclid(self, modulus: Self) -> Self;
+}
+
+macro_rules! numbers {
+    ($($t:ty),*) => {
+        $(
+            impl Number for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, other)
+                }
+
+                fn rem_euclid(self, modulus: Self) -> Self {
+                    <$t>::rem_euclid(self, modulus)
+                }
+            }
+        )*
+    };
+}
+
+numbers!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// How element-wise additions and multiplications are carried out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Arithmetic<T> {
+    /// Plain operators, which panic on overflow in debug builds.
+    Unchecked,
+    /// Fails with an error instead of overflowing.
+    Checked,
+    /// Reduces every result modulo the given modulus, failing if an intermediate
+    /// product overflows (pick a wide enough `T` for the modulus). A zero modulus is an error.
+    Modular(T),
+}
+
+impl<T: Number> Arithmetic<T> {
+    fn validate(self) -> Result<()> {
+        match self {
+            Self::Modular(modulus) if modulus == T::ZERO => {
+                Err(anyhow!("Modulus must be non-zero"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn reduce(self, value: T) -> T {
+        match self {
+            Self::Modular(modulus) => value.rem_euclid(modulus),
+            _ => value,
+        }
+    }
+
+    fn add(self, a: T, b: T) -> Result<T> {
+        match self {
+            Self::Unchecked => Ok(a + b),
+            _ => a
+                .checked_add(b)
+                .map(|sum| self.reduce(sum))
+                .ok_or_else(|| anyhow!("Overflow in matrix addition")),
+        }
+    }
+
+    fn mul(self, a: T, b: T) -> Result<T> {
+        match self {
+            Self::Unchecked => Ok(a * b),
+            _ => a
+                .checked_mul(b)
+                .map(|product| self.reduce(product))
+                .ok_or_else(|| anyhow!("Overflow in matrix multiplication")),
+        }
+    }
+}
+
+pub fn identity<T: Number>(size: usize) -> Grid<T> {
+    let mut result = Grid::new(size, size, T::ZERO);
+    for idx in 0..size {
+        result[(idx, idx)] = T::ONE;
+    }
+    result
+}
+
+pub fn add<T, A, B>(a: &A, b: &B, arithmetic: Arithmetic<T>) -> Result<Grid<T>>
+where
+    T: Number,
+    A: Matrix<T> + ?Sized,
+    B: Matrix<T> + ?Sized,
+{
+    if (a.rows(), a.cols()) != (b.rows(), b.cols()) {
+        return Err(anyhow!(
+            "Cannot add a {}x{} matrix to a {}x{} matrix",
+            a.rows(),
+            a.cols(),
+            b.rows(),
+            b.cols()
+        ));
+    }
+    arithmetic.validate()?;
+    let cells = (&a)
+        .iter_indexed()
+        .map(|(row, col, &value)| arithmetic.add(value, *b.get(row, col)))
+        .collect::<Result<Vec<_>>>()?;
+    Grid::from_cells(a.cols(), cells)
+}
+
+pub fn mul<T, A, B>(a: &A, b: &B, arithmetic: Arithmetic<T>) -> Result<Grid<T>>
+where
+    T: Number,
+    A: Matrix<T> + ?Sized,
+    B: Matrix<T> + ?Sized,
+{
+    if a.cols() != b.rows() {
+        return Err(anyhow!(
+            "Cannot multiply a {}x{} matrix by a {}x{} matrix",
+            a.rows(),
+            a.cols(),
+            b.rows(),
+            b.cols()
+        ));
+    }
+    arithmetic.validate()?;
+    let mut result = Grid::new(a.rows(), b.cols(), T::ZERO);
+    for row in 0..a.rows() {
+        for col in 0..b.cols() {
+            let mut sum = T::ZERO;
+            for idx in 0..a.cols() {
+                let product = arithmetic.mul(*a.get(row, idx), *b.get(idx, col))?;
+                sum = arithmetic.add(sum, product)?;
+            }
+            result[(row, col)] = sum;
+        }
+    }
+    Ok(result)
+}
+
+/// Raises a square matrix to `exponent` by repeated squaring, in `O(n^3 log exponent)`.
+pub fn pow<T, M>(matrix: &M, mut exponent: u64, arithmetic: Arithmetic<T>) -> Result<Grid<T>>
+where
+    T: Number,
+    M: Matrix<T> + ?Sized,
+{
+    if matrix.rows() != matrix.cols() {
+        return Err(anyhow!(
+            "Cannot raise a non-square {}x{} matrix to a power",
+            matrix.rows(),
+            matrix.cols()
+        ));
+    }
+    arithmetic.validate()?;
+    let mut base = Grid::try_from(matrix.to_vec_vec())?;
+    let mut result = identity(matrix.rows());
+    if let Arithmetic::Modular(_) = arithmetic {
+        result
+            .as_mut_slice()
+            .iter_mut()
+            .for_each(|value| *value = arithmetic.reduce(*value));
+    }
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul(&result, &base, arithmetic)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = mul(&base, &base, arithmetic)?;
+        }
+    }
+    Ok(result)
+}
+
+impl<T: Number> Grid<T> {
+    /// `self` to the power of `exponent` with plain arithmetic; see `algebra::pow`.
+    pub fn pow(&self, exponent: u64) -> Grid<T> {
+        pow(self, exponent, Arithmetic::Unchecked).unwrap()
+    }
+}
+
+/// Panics if the shapes don't match; use `algebra::add` to get an error instead.
+impl<T: Number> Add for &Grid<T> {
+    type Output = Grid<T>;
+
+    fn add(self, other: Self) -> Grid<T> {
+        add(self, other, Arithmetic::Unchecked).unwrap()
+    }
+}
+
+/// Panics if the shapes don't match; use `algebra::mul` to get an error instead.
+impl<T: Number> Mul for &Grid<T> {
+    type Output = Grid<T>;
+
+    fn mul(self, other: Self) -> Grid<T> {
+        mul(self, other, Arithmetic::Unchecked).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_and_mul() {
+        let a = Grid::try_from(vec![vec![1, 2], vec![3, 4]]).unwrap();
+        let b = Grid::try_from(vec![vec![5, 6], vec![7, 8]]).unwrap();
+        assert_eq!((&a + &b).into_vec_vec(), vec![vec![6, 8], vec![10, 12]]);
+        assert_eq!((&a * &b).into_vec_vec(), vec![vec![19, 22], vec![43, 50]]);
+        assert_eq!(&a * &identity(2), a);
+
+        let column = vec![vec![1], vec![1]];
+        assert_eq!(
+            mul(&a, &column, Arithmetic::Unchecked)
+                .unwrap()
+                .into_vec_vec(),
+            vec![vec![3], vec![7]]
+        );
+        assert!(mul(&column, &a, Arithmetic::Unchecked).is_err());
+        assert!(add(&a, &column, Arithmetic::Unchecked).is_err());
+        assert_eq!(
+            add(&a, &(&b).transpose(), Arithmetic::Modular(5))
+                .unwrap()
+                .into_vec_vec(),
+            vec![vec![1, 4], vec![4, 2]]
+        );
+    }
+
+    #[test]
+    fn test_pow() {
+        let fib = vec![vec![1u64, 1], vec![1, 0]];
+        assert_eq!(
+            pow(&fib, 0, Arithmetic::Unchecked).unwrap(),
+            identity::<u64>(2)
+        );
+        assert_eq!(pow(&fib, 10, Arithmetic::Unchecked).unwrap()[(0, 1)], 55);
+        assert_eq!(
+            Grid::try_from(fib.clone()).unwrap().pow(90)[(0, 1)],
+            2_880_067_194_370_816_120
+        );
+        assert_eq!(
+            pow(&fib, 1_000_000_000_000, Arithmetic::Modular(1_000_000_007)).unwrap()[(0, 1)],
+            730_695_249
+        );
+        assert!(pow(&fib, 100, Arithmetic::Checked).is_err());
+        assert!(pow(&vec![vec![1u64, 2]], 2, Arithmetic::Unchecked).is_err());
+        assert_eq!(
+            pow(&fib, 0, Arithmetic::Modular(1)).unwrap(),
+            Grid::new(2, 2, 0)
+        );
+    }
+
+    #[test]
+    fn test_zero_modulus() {
+        let m = vec![vec![1u64, 2], vec![3, 4]];
+        assert!(add(&m, &m, Arithmetic::Modular(0)).is_err());
+        assert!(mul(&m, &m, Arithmetic::Modular(0)).is_err());
+        assert!(pow(&m, 0, Arithmetic::Modular(0)).is_err());
+    }
+}
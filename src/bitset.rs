@@ -46,6 +46,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_empty() {
         assert_eq!(0u64.is_empty(), true);
         assert_eq!(0u64.len(), 0);
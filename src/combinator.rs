@@ -0,0 +1,461 @@
+use crate::parse::{Either, Parse, ParseRef};
+use anyhow::{anyhow, Result};
+use std::{fmt::Display, marker::PhantomData, str::FromStr};
+
+/// Parser consuming a prefix of the input and returning the unconsumed rest.
+pub trait Parser<'a> {
+    type Output;
+    fn parse_prefix(&self, input: &'a str) -> Result<(Self::Output, &'a str)>;
+
+    /// Parses the whole (trimmed) input, failing on leftovers.
+    fn parse_str(&self, input: &'a str) -> Result<Self::Output> {
+        let (output, rest) = self.parse_prefix(input.trim())?;
+        if rest.is_empty() {
+            Ok(output)
+        } else {
+            Err(anyhow!("Unexpected trailing input {:?}", rest))
+        }
+    }
+
+    fn map<F, O>(self, f: F) -> Map<Self, F>
+    where
+        Self: Sized,
+        F: Fn(Self::Output) -> O,
+    {
+        map(self, f)
+    }
+}
+
+/// Any `Fn(&str) -> Result<(O, &str)>` is a parser, which is handy for one-off rules.
+impl<'a, O, F> Parser<'a> for F
+where
+    F: Fn(&'a str) -> Result<(O, &'a str)>,
+{
+    type Output = O;
+
+    fn parse_prefix(&self, input: &'a str) -> Result<(Self::Output, &'a str)> {
+        self(input)
+    }
+}
+
+fn context(input: &str) -> String {
+    input.chars().take(20).collect()
+}
+
+pub struct Tag {
+    tag: &'static str,
+}
+
+/// Matches `tag` literally.
+pub fn tag(tag: &'static str) -> Tag {
+    Tag { tag }
+}
+
+impl<'a> Parser<'a> for Tag {
+    type Output = &'a str;
+
+    fn parse_prefix(&self, input: &'a str) -> Result<(Self::Output, &'a str)> {
+        if input.starts_with(self.tag) {
+            Ok(input.split_at(self.tag.len()))
+        } else {
+            Err(anyhow!("Expected {:?} at {:?}", self.tag, context(input)))
+        }
+    }
+}
+
+pub struct TakeWhile<F> {
+    predicate: F,
+    min: usize,
+}
+
+/// Takes the longest prefix whose chars match `predicate` (possibly empty).
+pub fn take_while<F: Fn(char) -> bool>(predicate: F) -> TakeWhile<F> {
+    TakeWhile { predicate, min: 0 }
+}
+
+/// Like `take_while` but requires at least one matching char.
+pub fn take_while1<F: Fn(char) -> bool>(predicate: F) -> TakeWhile<F> {
+    TakeWhile { predicate, min: 1 }
+}
+
+impl<'a, F: Fn(char) -> bool> Parser<'a> for TakeWhile<F> {
+    type Output = &'a str;
+
+    fn parse_prefix(&self, input: &'a str) -> Result<(Self::Output, &'a str)> {
+        let end = input
+            .char_indices()
+            .find(|(_, c)| !(self.predicate)(*c))
+            .map(|(idx, _)| idx)
+            .unwrap_or(input.len());
+        if input[..end].chars().count() < self.min {
+            Err(anyhow!("Expected a match at {:?}", context(input)))
+        } else {
+            Ok(input.split_at(end))
+        }
+    }
+}
+
+/// Non-empty run of alphanumeric chars or `_`.
+pub fn word() -> TakeWhile<fn(char) -> bool> {
+    take_while1(|c| c.is_alphanumeric() || c == '_')
+}
+
+pub struct Number<T> {
+    _phantom: PhantomData<T>,
+}
+
+/// Optionally signed run of digits parsed as `T`.
+pub fn number<T>() -> Number<T> {
+    Number {
+        _phantom: PhantomData,
+    }
+}
+
+impl<'a, T: FromStr> Parser<'a> for Number<T>
+where
+    T::Err: Display,
+{
+    type Output = T;
+
+    fn parse_prefix(&self, input: &'a str) -> Result<(Self::Output, &'a str)> {
+        let sign = usize::from(input.starts_with(['-', '+']));
+        let end = sign
+            + input[sign..]
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(input.len() - sign);
+        if end == sign {
+            return Err(anyhow!("Expected a number at {:?}", context(input)));
+        }
+        let (digits, rest) = input.split_at(end);
+        digits
+            .parse::<T>()
+            .map(|n| (n, rest))
+            .map_err(|e| anyhow!("Parse failed: {}", e))
+    }
+}
+
+pub struct Ws<P> {
+    parser: P,
+}
+
+/// Runs `parser` after skipping leading whitespace.
+pub fn ws<P>(parser: P) -> Ws<P> {
+    Ws { parser }
+}
+
+impl<'a, P: Parser<'a>> Parser<'a> for Ws<P> {
+    type Output = P::Output;
+
+    fn parse_prefix(&self, input: &'a str) -> Result<(Self::Output, &'a str)> {
+        self.parser.parse_prefix(input.trim_start())
+    }
+}
+
+pub struct Many<P> {
+    parser: P,
+    min: usize,
+}
+
+/// Applies `parser` as many times as possible (possibly zero).
+pub fn many<P>(parser: P) -> Many<P> {
+    Many { parser, min: 0 }
+}
+
+/// Like `many` but requires at least one match.
+pub fn many1<P>(parser: P) -> Many<P> {
+    Many { parser, min: 1 }
+}
+
+impl<'a, P: Parser<'a>> Parser<'a> for Many<P> {
+    type Output = Vec<P::Output>;
+
+    fn parse_prefix(&self, mut input: &'a str) -> Result<(Self::Output, &'a str)> {
+        let mut outputs = Vec::new();
+        loop {
+            match self.parser.parse_prefix(input) {
+                // stop on parsers that succeed without consuming anything
+                Ok((output, rest)) if rest.len() < input.len() => {
+                    outputs.push(output);
+                    input = rest;
+                }
+                Ok(_) => break,
+                Err(e) if outputs.len() < self.min => return Err(e),
+                Err(_) => break,
+            }
+        }
+        if outputs.len() < self.min {
+            Err(anyhow!(
+                "Expected at least {} matches at {:?}",
+                self.min,
+                context(input)
+            ))
+        } else {
+            Ok((outputs, input))
+        }
+    }
+}
+
+pub struct Separated<P, S> {
+    parser: P,
+    separator: S,
+}
+
+/// Zero or more `parser` matches separated by `separator`.
+pub fn separated<P, S>(parser: P, separator: S) -> Separated<P, S> {
+    Separated { parser, separator }
+}
+
+impl<'a, P: Parser<'a>, S: Parser<'a>> Parser<'a> for Separated<P, S> {
+    type Output = Vec<P::Output>;
+
+    fn parse_prefix(&self, input: &'a str) -> Result<(Self::Output, &'a str)> {
+        let mut outputs = Vec::new();
+        let mut input = match self.parser.parse_prefix(input) {
+            Ok((output, rest)) => {
+                outputs.push(output);
+                rest
+            }
+            Err(_) => return Ok((outputs, input)),
+        };
+        while let Ok((_, after_separator)) = self.separator.parse_prefix(input) {
+            match self.parser.parse_prefix(after_separator) {
+                Ok((output, rest)) => {
+                    outputs.push(output);
+                    input = rest;
+                }
+                Err(_) => break,
+            }
+        }
+        Ok((outputs, input))
+    }
+}
+
+pub struct Opt<P> {
+    parser: P,
+}
+
+/// Makes `parser` optional.
+pub fn opt<P>(parser: P) -> Opt<P> {
+    Opt { parser }
+}
+
+impl<'a, P: Parser<'a>> Parser<'a> for Opt<P> {
+    type Output = Option<P::Output>;
+
+    fn parse_prefix(&self, input: &'a str) -> Result<(Self::Output, &'a str)> {
+        match self.parser.parse_prefix(input) {
+            Ok((output, rest)) => Ok((Some(output), rest)),
+            Err(_) => Ok((None, input)),
+        }
+    }
+}
+
+pub struct Map<P, F> {
+    parser: P,
+    f: F,
+}
+
+/// Transforms the output of `parser` with `f`.
+pub fn map<P, F>(parser: P, f: F) -> Map<P, F> {
+    Map { parser, f }
+}
+
+impl<'a, P: Parser<'a>, F: Fn(P::Output) -> O, O> Parser<'a> for Map<P, F> {
+    type Output = O;
+
+    fn parse_prefix(&self, input: &'a str) -> Result<(Self::Output, &'a str)> {
+        self.parser
+            .parse_prefix(input)
+            .map(|(output, rest)| ((self.f)(output), rest))
+    }
+}
+
+pub struct Alt<A, B> {
+    left: A,
+    right: B,
+}
+
+/// Tries `left` and then `right`, reporting both errors if neither matches.
+pub fn alt<A, B>(left: A, right: B) -> Alt<A, B> {
+    Alt { left, right }
+}
+
+impl<'a, A: Parser<'a>, B: Parser<'a>> Parser<'a> for Alt<A, B> {
+    type Output = Either<A::Output, B::Output>;
+
+    fn parse_prefix(&self, input: &'a str) -> Result<(Self::Output, &'a str)> {
+        match self.left.parse_prefix(input) {
+            Ok((output, rest)) => Ok((Either::Left(output), rest)),
+            Err(left) => match self.right.parse_prefix(input) {
+                Ok((output, rest)) => Ok((Either::Right(output), rest)),
+                Err(right) => Err(anyhow!("{}; {}", left, right)),
+            },
+        }
+    }
+}
+
+/// Runs `open`, `parser` and `close`, keeping only the output of `parser`.
+pub fn delimited<'a, L, P, R>(open: L, parser: P, close: R) -> impl Parser<'a, Output = P::Output>
+where
+    L: Parser<'a>,
+    P: Parser<'a>,
+    R: Parser<'a>,
+{
+    map((open, parser, close), |(_, output, _)| output)
+}
+
+/// Runs `prefix` and then `parser`, keeping only the output of `parser`.
+pub fn preceded<'a, L, P>(prefix: L, parser: P) -> impl Parser<'a, Output = P::Output>
+where
+    L: Parser<'a>,
+    P: Parser<'a>,
+{
+    map((prefix, parser), |(_, output)| output)
+}
+
+/// Runs `parser` and then `suffix`, keeping only the output of `parser`.
+pub fn terminated<'a, P, R>(parser: P, suffix: R) -> impl Parser<'a, Output = P::Output>
+where
+    P: Parser<'a>,
+    R: Parser<'a>,
+{
+    map((parser, suffix), |(output, _)| output)
+}
+
+macro_rules! sequence {
+    ($($name:ident),+) => {
+        /// Runs the parsers one after another and collects their outputs.
+        impl<'a, $($name: Parser<'a>),+> Parser<'a> for ($($name,)+) {
+            type Output = ($($name::Output,)+);
+
+            #[allow(non_snake_case)]
+            fn parse_prefix(&self, input: &'a str) -> Result<(Self::Output, &'a str)> {
+                let ($($name,)+) = self;
+                $(
+                    let ($name, input) = $name.parse_prefix(input)?;
+                )+
+                Ok((($($name,)+), input))
+            }
+        }
+    };
+}
+
+sequence!(A, B);
+sequence!(A, B, C);
+sequence!(A, B, C, D);
+sequence!(A, B, C, D, E);
+sequence!(A, B, C, D, E, F);
+sequence!(A, B, C, D, E, F, G);
+sequence!(A, B, C, D, E, F, G, H);
+
+/// Grammar built from the combinators above that can be plugged into `parse`
+/// as `WithGrammar<Self>`.
+pub trait Grammar {
+    type Output;
+    fn parser<'a>() -> impl Parser<'a, Output = Self::Output>;
+}
+
+pub struct WithGrammar<G> {
+    _phantom: PhantomData<G>,
+}
+
+impl<G: Grammar> Parse for WithGrammar<G> {
+    type Parsed = G::Output;
+    fn parse(raw_input: &str) -> Result<Self::Parsed> {
+        G::parser().parse_str(raw_input)
+    }
+}
+
+impl<'a, G: Grammar> ParseRef<'a> for WithGrammar<G> {
+    type Parsed = G::Output;
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        G::parser().parse_str(raw_input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::VecFromLines;
+
+    #[test]
+    fn test_primitives() {
+        assert_eq!(tag("move").parse_prefix("move 1").unwrap(), ("move", " 1"));
+        assert!(tag("move").parse_prefix("mov").is_err());
+        assert_eq!(number::<isize>().parse_prefix("-12x").unwrap(), (-12, "x"));
+        assert!(number::<usize>().parse_prefix("-").is_err());
+        assert!(number::<u8>().parse_prefix("300").is_err());
+        assert_eq!(word().parse_str(" abc_1 ").unwrap(), "abc_1");
+        assert_eq!(ws(number::<usize>()).parse_prefix("  7").unwrap(), (7, ""));
+        assert_eq!(opt(tag("x")).parse_prefix("y").unwrap(), (None, "y"));
+        assert!(matches!(
+            alt(number::<usize>(), word()).parse_str("abc").unwrap(),
+            Either::Right("abc")
+        ));
+    }
+
+    #[test]
+    fn test_composed() {
+        let list = delimited(
+            tag("["),
+            separated(ws(number::<usize>()), tag(",")),
+            tag("]"),
+        );
+        assert_eq!(list.parse_str("[1, 2,3]").unwrap(), vec![1, 2, 3]);
+        assert_eq!(list.parse_str("[]").unwrap(), vec![]);
+        assert!(list.parse_str("[1,2").is_err());
+
+        assert_eq!(
+            many(terminated(word(), opt(tag(" "))))
+                .parse_str("a b c")
+                .unwrap(),
+            vec!["a", "b", "c"]
+        );
+        assert!(many1(word()).parse_str("").is_err());
+
+        let signed =
+            |input| map(preceded(tag("x="), number::<isize>()), |x| x * 2).parse_prefix(input);
+        assert_eq!(signed.parse_str("x=-3").unwrap(), -6);
+        assert!(signed.parse_str("x=-3 y").is_err());
+    }
+
+    #[test]
+    fn test_grammar() {
+        #[derive(Debug, PartialEq, Eq)]
+        struct Move {
+            count: usize,
+            from: usize,
+            to: usize,
+        }
+
+        impl Grammar for Move {
+            type Output = Self;
+            fn parser<'a>() -> impl Parser<'a, Output = Self> {
+                (
+                    preceded(tag("move "), number()),
+                    preceded(tag(" from "), number()),
+                    preceded(tag(" to "), number()),
+                )
+                    .map(|(count, from, to)| Self { count, from, to })
+            }
+        }
+
+        assert_eq!(
+            VecFromLines::<WithGrammar<Move>>::parse("move 1 from 2 to 3\nmove 4 from 5 to 6")
+                .unwrap(),
+            vec![
+                Move {
+                    count: 1,
+                    from: 2,
+                    to: 3
+                },
+                Move {
+                    count: 4,
+                    from: 5,
+                    to: 6
+                },
+            ]
+        );
+        assert!(WithGrammar::<Move>::parse("move 1 from 2").is_err());
+    }
+}
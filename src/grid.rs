@@ -0,0 +1,205 @@
+use crate::matrix::{self, Matrix, MatrixMut};
+use crate::tile_map::TileMap;
+use anyhow::{anyhow, Result};
+use std::{
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+};
+
+/// Owned matrix stored row by row in a single `Vec`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(rows: usize, cols: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![fill; rows * cols],
+            rows,
+            cols,
+        }
+    }
+
+    /// Builds a grid from row-major `cells`, which must hold a multiple of `cols` items.
+    pub fn from_cells(cols: usize, cells: Vec<T>) -> Result<Self> {
+        let fits = if cols == 0 {
+            cells.is_empty()
+        } else {
+            cells.len().is_multiple_of(cols)
+        };
+        if !fits {
+            return Err(anyhow!(
+                "{} cells don't fill rows of {} columns",
+                cells.len(),
+                cols
+            ));
+        }
+        let rows = cells.len().checked_div(cols).unwrap_or(0);
+        Ok(Self { cells, rows, cols })
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        &mut self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn into_vec_vec(self) -> Vec<Vec<T>> {
+        let mut cells = self.cells.into_iter();
+        (0..self.rows)
+            .map(|_| cells.by_ref().take(self.cols).collect())
+            .collect()
+    }
+}
+
+impl<T> Matrix<T> for Grid<T> {
+    fn get(&self, row: usize, col: usize) -> &T {
+        &self[(row, col)]
+    }
+
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+}
+
+impl<T> MatrixMut<T> for Grid<T> {
+    fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+        &mut self[(row, col)]
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        assert!(
+            row < self.rows && col < self.cols,
+            "({}, {}) out of bounds for {}x{} grid",
+            row,
+            col,
+            self.rows,
+            self.cols
+        );
+        &self.cells[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        assert!(
+            row < self.rows && col < self.cols,
+            "({}, {}) out of bounds for {}x{} grid",
+            row,
+            col,
+            self.rows,
+            self.cols
+        );
+        &mut self.cells[row * self.cols + col]
+    }
+}
+
+impl Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(|&tile| tile).fmt(f)
+    }
+}
+
+/// Accepts the output of `RowsOfChars`; all rows must have the same length.
+impl<T> TryFrom<Vec<Vec<T>>> for Grid<T> {
+    type Error = anyhow::Error;
+
+    fn try_from(rows: Vec<Vec<T>>) -> Result<Self> {
+        let rows = matrix::rectangular(rows)?;
+        let cols = rows.first().map_or(0, Vec::len);
+        let row_count = rows.len();
+        Ok(Self {
+            cells: rows.into_iter().flatten().collect(),
+            rows: row_count,
+            cols,
+        })
+    }
+}
+
+impl<T: Copy> TryFrom<TileMap<T>> for Grid<T> {
+    type Error = anyhow::Error;
+
+    fn try_from(tile_map: TileMap<T>) -> Result<Self> {
+        Self::try_from(Vec::from(tile_map))
+    }
+}
+
+impl<T: Copy> From<Grid<T>> for TileMap<T> {
+    fn from(grid: Grid<T>) -> Self {
+        Self::from(grid.into_vec_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constructors() {
+        let g = Grid::new(2, 3, '.');
+        assert_eq!((g.rows(), g.cols()), (2, 3));
+        assert_eq!(g.get(1, 2), &'.');
+
+        let g = Grid::try_from(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+        assert_eq!(g.get(1, 0), &4);
+        assert_eq!(g.row(1), &[4, 5, 6]);
+        assert_eq!(g, Grid::from_cells(3, vec![1, 2, 3, 4, 5, 6]).unwrap());
+        assert!(Grid::try_from(vec![vec![1, 2], vec![3]]).is_err());
+        assert!(Grid::from_cells(4, vec![1, 2, 3]).is_err());
+
+        let empty = Grid::<u8>::try_from(vec![]).unwrap();
+        assert_eq!((empty.rows(), empty.cols()), (0, 0));
+    }
+
+    #[test]
+    fn test_matrix_and_views() {
+        let mut g = Grid::try_from(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+        g.set(0, 0, 10);
+        g[(1, 2)] = 60;
+        assert_eq!(g.to_vec_vec(), vec![vec![10, 2, 3], vec![4, 5, 60]]);
+        assert_eq!(
+            (&g).rotate().to_vec_vec(),
+            vec![vec![3, 60], vec![2, 5], vec![10, 4]]
+        );
+        assert_eq!(
+            g.iter_by_rows().copied().collect::<Vec<_>>(),
+            vec![10, 2, 3, 4, 5, 60]
+        );
+    }
+
+    #[test]
+    fn test_tile_map_conversions() {
+        let g = Grid::try_from(vec![vec!['#', '.'], vec!['.', '#']]).unwrap();
+        let tile_map = TileMap::from(g.clone());
+        assert_eq!(tile_map.get(1, 1), Some('#'));
+        assert_eq!(Grid::try_from(tile_map).unwrap(), g);
+    }
+}
@@ -1,11 +1,17 @@
 pub use anyhow;
+pub mod algebra;
 pub mod bfs;
 pub mod bitset;
+pub mod combinator;
+pub mod grid;
 pub mod interpret;
+pub mod linear;
 pub mod matrix;
 pub mod parse;
 pub mod permutations;
 pub mod prelude;
+pub mod render;
 pub mod scaffold;
+pub mod sparse;
 pub mod tile_map;
 pub mod walk;
@@ -0,0 +1,408 @@
+use crate::matrix::Matrix;
+use anyhow::{anyhow, Result};
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+};
+
+/// Exact fraction in lowest terms with a positive denominator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+impl Rational {
+    pub const ZERO: Self = Self { num: 0, den: 1 };
+    pub const ONE: Self = Self { num: 1, den: 1 };
+
+    /// Panics if `den` is zero.
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "Rational with zero denominator");
+        let divisor = gcd(num, den) * den.signum();
+        Self {
+            num: num / divisor,
+            den: den / divisor,
+        }
+    }
+
+    pub fn num(self) -> i128 {
+        self.num
+    }
+
+    pub fn den(self) -> i128 {
+        self.den
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.num == 0
+    }
+
+    /// The value if it is a whole number, e.g. to reject fractional button presses.
+    pub fn to_integer(self) -> Option<i128> {
+        (self.den == 1).then_some(self.num)
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let divisor = gcd(self.den, other.den);
+        let num = self
+            .num
+            .checked_mul(other.den / divisor)?
+            .checked_add(other.num.checked_mul(self.den / divisor)?)?;
+        Some(Self::new(num, (self.den / divisor).checked_mul(other.den)?))
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(Self {
+            num: other.num.checked_neg()?,
+            den: other.den,
+        })
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let first = gcd(self.num, other.den).max(1);
+        let second = gcd(other.num, self.den).max(1);
+        Some(Self::new(
+            (self.num / first).checked_mul(other.num / second)?,
+            (self.den / second).checked_mul(other.den / first)?,
+        ))
+    }
+
+    /// `None` on overflow or division by zero.
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        self.checked_mul(Self::new(other.den, other.num))
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(num: i128) -> Self {
+        Self { num, den: 1 }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if let (Some(left), Some(right)) = (
+            self.num.checked_mul(other.den),
+            other.num.checked_mul(self.den),
+        ) {
+            return left.cmp(&right);
+        }
+        // Compare the integer parts, then the fractional parts by their reciprocals,
+        // like a continued fraction expansion, so nothing can overflow.
+        let (left, right) = (
+            self.num.div_euclid(self.den),
+            other.num.div_euclid(other.den),
+        );
+        left.cmp(&right).then_with(|| {
+            let (left, right) = (
+                self.num.rem_euclid(self.den),
+                other.num.rem_euclid(other.den),
+            );
+            match (left, right) {
+                (0, 0) => Ordering::Equal,
+                (0, _) => Ordering::Less,
+                (_, 0) => Ordering::Greater,
+                _ => Self {
+                    num: other.den,
+                    den: right,
+                }
+                .cmp(&Self {
+                    num: self.den,
+                    den: left,
+                }),
+            }
+        })
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+fn overflow() -> anyhow::Error {
+    anyhow!("Overflow in exact elimination")
+}
+
+/// Outcome of `solve`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Solution {
+    Unique(Vec<Rational>),
+    NoSolution,
+    /// One solution with every free variable set to zero, plus the indices of
+    /// the free variables.
+    Infinite {
+        particular: Vec<Rational>,
+        free: Vec<usize>,
+    },
+}
+
+/// Matrix of rationals reduced to row echelon form.
+struct Echelon {
+    rows: Vec<Vec<Rational>>,
+    /// Column of the pivot in each of the first `pivots.len()` rows.
+    pivots: Vec<usize>,
+    /// Product of the pivots, negated for every row swap.
+    determinant: Rational,
+}
+
+fn to_rational<T: TryInto<i128>>(value: T) -> Result<Rational> {
+    value
+        .try_into()
+        .map(Rational::from)
+        .map_err(|_| anyhow!("Value doesn't fit in an i128"))
+}
+
+fn to_rationals<T, M>(matrix: &M) -> Result<Vec<Vec<Rational>>>
+where
+    T: Copy + TryInto<i128>,
+    M: Matrix<T> + ?Sized,
+{
+    (0..matrix.rows())
+        .map(|row| {
+            (&matrix)
+                .iter_row(row)
+                .map(|&value| to_rational(value))
+                .collect()
+        })
+        .collect()
+}
+
+/// Gauss-Jordan elimination over the first `cols` columns of `rows`.
+fn eliminate(mut rows: Vec<Vec<Rational>>, cols: usize) -> Result<Echelon> {
+    let mut pivots = Vec::new();
+    let mut determinant = Rational::ONE;
+    for col in 0..cols {
+        let pivot_row = pivots.len();
+        let Some(found) = (pivot_row..rows.len()).find(|&row| !rows[row][col].is_zero()) else {
+            determinant = Rational::ZERO;
+            continue;
+        };
+        if found != pivot_row {
+            rows.swap(found, pivot_row);
+            determinant = determinant.checked_mul(Rational::from(-1)).unwrap();
+        }
+        let pivot = rows[pivot_row][col];
+        determinant = determinant.checked_mul(pivot).ok_or_else(overflow)?;
+        for value in rows[pivot_row].iter_mut() {
+            *value = value.checked_div(pivot).ok_or_else(overflow)?;
+        }
+        for row in 0..rows.len() {
+            let factor = rows[row][col];
+            if row == pivot_row || factor.is_zero() {
+                continue;
+            }
+            for idx in col..rows[row].len() {
+                let scaled = rows[pivot_row][idx]
+                    .checked_mul(factor)
+                    .ok_or_else(overflow)?;
+                rows[row][idx] = rows[row][idx].checked_sub(scaled).ok_or_else(overflow)?;
+            }
+        }
+        pivots.push(col);
+    }
+    Ok(Echelon {
+        rows,
+        pivots,
+        determinant,
+    })
+}
+
+/// Solves `coefficients * x = rhs` exactly.
+///
+/// Intermediate values are `i128` fractions, so inputs around `10^14` are fine
+/// for the small systems puzzles use; overflow is reported as an error.
+pub fn solve<T, M>(coefficients: &M, rhs: &[T]) -> Result<Solution>
+where
+    T: Copy + TryInto<i128>,
+    M: Matrix<T> + ?Sized,
+{
+    if coefficients.rows() != rhs.len() {
+        return Err(anyhow!(
+            "{} equations but {} right-hand sides",
+            coefficients.rows(),
+            rhs.len()
+        ));
+    }
+    let vars = coefficients.cols();
+    let mut rows = to_rationals(coefficients)?;
+    for (row, &value) in rows.iter_mut().zip(rhs) {
+        row.truncate(vars);
+        row.push(to_rational(value)?);
+    }
+    let Echelon { rows, pivots, .. } = eliminate(rows, vars)?;
+    if rows[pivots.len()..].iter().any(|row| !row[vars].is_zero()) {
+        return Ok(Solution::NoSolution);
+    }
+    let mut solution = vec![Rational::ZERO; vars];
+    for (row, &col) in pivots.iter().enumerate() {
+        solution[col] = rows[row][vars];
+    }
+    if pivots.len() == vars {
+        Ok(Solution::Unique(solution))
+    } else {
+        let free = (0..vars).filter(|col| !pivots.contains(col)).collect();
+        Ok(Solution::Infinite {
+            particular: solution,
+            free,
+        })
+    }
+}
+
+pub fn determinant<T, M>(matrix: &M) -> Result<Rational>
+where
+    T: Copy + TryInto<i128>,
+    M: Matrix<T> + ?Sized,
+{
+    if matrix.rows() != matrix.cols() {
+        return Err(anyhow!(
+            "Determinant of a non-square {}x{} matrix",
+            matrix.rows(),
+            matrix.cols()
+        ));
+    }
+    Ok(eliminate(to_rationals(matrix)?, matrix.cols())?.determinant)
+}
+
+pub fn rank<T, M>(matrix: &M) -> Result<usize>
+where
+    T: Copy + TryInto<i128>,
+    M: Matrix<T> + ?Sized,
+{
+    Ok(eliminate(to_rationals(matrix)?, matrix.cols())?
+        .pivots
+        .len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rational() {
+        assert_eq!(Rational::new(4, -6), Rational::new(-2, 3));
+        assert_eq!(Rational::new(-2, 3).to_string(), "-2/3");
+        assert_eq!(Rational::new(6, 3).to_integer(), Some(2));
+        assert_eq!(
+            Rational::new(1, 2).checked_add(Rational::new(1, 3)),
+            Some(Rational::new(5, 6))
+        );
+        assert_eq!(
+            Rational::new(1, 2).checked_div(Rational::new(-1, 4)),
+            Some(Rational::from(-2))
+        );
+        assert_eq!(Rational::ONE.checked_div(Rational::ZERO), None);
+        assert!(Rational::new(1, 3) < Rational::new(1, 2));
+        assert!(Rational::new(-1, 2) < Rational::new(-1, 3));
+    }
+
+    #[test]
+    fn test_rational_cmp_without_overflow() {
+        let max = i128::MAX;
+        assert!(Rational::new(max, 3) < Rational::new(max, 2));
+        assert!(Rational::new(-max, 2) < Rational::new(-max, 3));
+        assert!(Rational::new(max - 1, max) < Rational::ONE);
+        assert!(Rational::new(max - 1, max) > Rational::new(max - 2, max - 1));
+        assert!(Rational::new(max, max - 1) < Rational::new(max - 1, max - 2));
+        assert_eq!(
+            Rational::new(max - 1, max).cmp(&Rational::new(max - 1, max)),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_solve_unique() {
+        // Claw machine: A = (94, 34), B = (22, 67), prize at (8400, 5400).
+        let coefficients = vec![vec![94i64, 22], vec![34, 67]];
+        assert_eq!(
+            solve(&coefficients, &[8400i64, 5400]).unwrap(),
+            Solution::Unique(vec![Rational::from(80), Rational::from(40)])
+        );
+
+        let prize = 10_000_000_000_000i64;
+        let Solution::Unique(presses) =
+            solve(&coefficients, &[prize + 8400, prize + 5400]).unwrap()
+        else {
+            panic!("expected a unique solution");
+        };
+        assert!(presses.iter().any(|value| value.to_integer().is_none()));
+
+        let Solution::Unique(presses) = solve(
+            &vec![vec![26i64, 67], vec![66, 21]],
+            &[prize + 12748, prize + 12176],
+        )
+        .unwrap() else {
+            panic!("expected a unique solution");
+        };
+        assert_eq!(
+            presses,
+            vec![Rational::from(118679050709), Rational::from(103199174542)]
+        );
+    }
+
+    #[test]
+    fn test_solve_degenerate() {
+        let coefficients = vec![vec![1i32, 2], vec![2, 4]];
+        assert_eq!(solve(&coefficients, &[3, 7]).unwrap(), Solution::NoSolution);
+        assert_eq!(
+            solve(&coefficients, &[3, 6]).unwrap(),
+            Solution::Infinite {
+                particular: vec![Rational::from(3), Rational::ZERO],
+                free: vec![1],
+            }
+        );
+        assert!(solve(&coefficients, &[1]).is_err());
+    }
+
+    #[test]
+    fn test_determinant_and_rank() {
+        let m = vec![vec![2i64, 0, 1], vec![1, 3, 2], vec![1, 1, 2]];
+        assert_eq!(determinant(&m).unwrap(), Rational::from(6));
+        assert_eq!(determinant(&(&m).transpose()).unwrap(), Rational::from(6));
+        assert_eq!(
+            determinant(&vec![vec![0i64, 1], vec![1, 0]]).unwrap(),
+            Rational::from(-1)
+        );
+        assert_eq!(rank(&m).unwrap(), 3);
+        assert_eq!(
+            determinant(&vec![vec![1i64, 2], vec![2, 4]]).unwrap(),
+            Rational::ZERO
+        );
+        assert_eq!(rank(&vec![vec![1i64, 2, 3], vec![2, 4, 6]]).unwrap(), 1);
+        assert!(determinant(&vec![vec![1i64, 2]]).is_err());
+    }
+
+    #[test]
+    fn test_pointer_sized_and_wide_inputs() {
+        let m = vec![vec![2usize, 0, 1], vec![1, 3, 2], vec![1, 1, 2]];
+        assert_eq!(determinant(&m).unwrap(), Rational::from(6));
+        assert_eq!(rank(&m).unwrap(), 3);
+        assert_eq!(
+            solve(&vec![vec![1isize, 1], vec![1, -1]], &[4isize, -2]).unwrap(),
+            Solution::Unique(vec![Rational::from(1), Rational::from(3)])
+        );
+        assert!(determinant(&vec![vec![u128::MAX]]).is_err());
+    }
+}
@@ -1,10 +1,44 @@
-use std::ops::{Index, Range};
+pub mod jigsaw;
+pub mod prefix_sum;
 
-pub trait Matrix<'a, T> {
+use crate::{
+    grid::Grid,
+    render::{Diff, Render},
+};
+use anyhow::{anyhow, Result};
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    marker::PhantomData,
+    ops::Range,
+};
+
+/// Read access to a 2D matrix.
+///
+/// Views (flips, rotations, slices, ...) take the matrix by value so they can be
+/// chained freely; they own their inner matrix or borrow it when created from
+/// `&m` (or `&mut m` for writing through `MatrixMut`).
+///
+/// Helpers returning views or iterators require `Self: Sized`, so the trait stays
+/// usable as `&dyn Matrix<T>`; call them on `&matrix` when `matrix` is unsized.
+pub trait Matrix<T> {
     fn get(&self, row: usize, col: usize) -> &T;
     fn rows(&self) -> usize;
     fn cols(&self) -> usize;
 
+    /// Like `get`, but returns `None` instead of panicking when out of bounds.
+    fn try_get(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.rows() && col < self.cols() {
+            Some(self.get(row, col))
+        } else {
+            None
+        }
+    }
+
+    /// Signed lookup for neighbour arithmetic, like `TileMap::get`.
+    fn get_signed(&self, row: isize, col: isize) -> Option<&T> {
+        self.try_get(usize::try_from(row).ok()?, usize::try_from(col).ok()?)
+    }
+
     fn to_vec_vec(&self) -> Vec<Vec<T>>
     where
         T: Clone,
@@ -18,35 +52,74 @@ pub trait Matrix<'a, T> {
             .collect()
     }
 
-    fn flip_vertically(&'a self) -> VerticallyFlipped<'a, Self>
+    fn flip_vertically(self) -> VerticallyFlipped<Self>
     where
         Self: Sized,
     {
         VerticallyFlipped::wrap(self)
     }
 
-    fn flip_horizontally(&'a self) -> HorizontallyFlipped<'a, Self>
+    fn flip_horizontally(self) -> HorizontallyFlipped<Self>
     where
         Self: Sized,
     {
         HorizontallyFlipped::wrap(self)
     }
 
-    fn rotate(&'a self) -> Rotated<'a, Self>
+    /// Rotates counter-clockwise by 90 degrees.
+    fn rotate(self) -> Rotated<Self>
     where
         Self: Sized,
     {
         Rotated::wrap(self)
     }
 
-    fn slice(&'a self, rows_range: Range<usize>, cols_range: Range<usize>) -> Sliced<'a, Self>
+    fn transpose(self) -> Transposed<Self>
+    where
+        Self: Sized,
+    {
+        Transposed::wrap(self)
+    }
+
+    fn rotate_clockwise(self) -> Oriented<Self>
+    where
+        Self: Sized,
+    {
+        Oriented::wrap(self, Orientation::RotatedClockwise)
+    }
+
+    fn rotate_180(self) -> Oriented<Self>
+    where
+        Self: Sized,
+    {
+        Oriented::wrap(self, Orientation::Rotated180)
+    }
+
+    fn orient(self, orientation: Orientation) -> Oriented<Self>
+    where
+        Self: Sized,
+    {
+        Oriented::wrap(self, orientation)
+    }
+
+    /// All 8 rotations and reflections, starting with the identity.
+    fn orientations(&self) -> impl Iterator<Item = Oriented<&Self>>
+    where
+        Self: Sized,
+    {
+        Orientation::ALL
+            .into_iter()
+            .map(move |orientation| Oriented::wrap(self, orientation))
+    }
+
+    fn slice(self, rows_range: Range<usize>, cols_range: Range<usize>) -> Sliced<Self>
     where
         Self: Sized,
     {
         Sliced::slice(self, rows_range, cols_range)
     }
 
-    fn iter_by_rows(&'a self) -> MatrixIterator<'a, T>
+    fn iter_by_rows(&self) -> MatrixIterator<'_, Self, T>
     where
         Self: Sized,
     {
@@ -54,13 +127,318 @@ pub trait Matrix<'a, T> {
             matrix: self,
             row: 0,
             col: 0,
+            _phantom: PhantomData,
         }
     }
+
+    /// Row-major iteration yielding `(row, col, &value)`.
+    fn iter_indexed<'a>(&'a self) -> impl Iterator<Item = (usize, usize, &'a T)>
+    where
+        Self: Sized,
+        T: 'a,
+    {
+        (0..self.rows())
+            .flat_map(move |row| (0..self.cols()).map(move |col| (row, col, self.get(row, col))))
+    }
+
+    /// Row-major positions of the cells matching `pred`.
+    fn positions<'a, F>(&'a self, mut pred: F) -> impl Iterator<Item = (usize, usize)>
+    where
+        Self: Sized,
+        T: 'a,
+        F: FnMut(&T) -> bool,
+    {
+        self.iter_indexed()
+            .filter_map(move |(row, col, value)| pred(value).then_some((row, col)))
+    }
+
+    /// Displayable rendering mapping each cell to a char with `tile`.
+    fn render<F>(&self, tile: F) -> Render<'_, Self, T, F>
+    where
+        Self: Sized,
+        F: Fn(&T) -> char,
+    {
+        Render::new(self, tile)
+    }
+
+    /// Compares with a matrix of the same size; the result displays both side by side.
+    fn diff<'a, O, F>(&'a self, other: &'a O, tile: F) -> Result<Diff<'a, Self, O, T, F>>
+    where
+        Self: Sized,
+        O: Matrix<T> + ?Sized,
+        T: PartialEq,
+        F: Fn(&T) -> char,
+    {
+        Diff::new(self, other, tile)
+    }
+
+    /// The cells of one row, left to right; use `.rev()` to walk right to left.
+    fn iter_row<'a>(
+        &'a self,
+        row: usize,
+    ) -> impl DoubleEndedIterator<Item = &'a T> + ExactSizeIterator
+    where
+        Self: Sized,
+        T: 'a,
+    {
+        (0..self.cols()).map(move |col| self.get(row, col))
+    }
+
+    /// The cells of one column, top to bottom; use `.rev()` to walk bottom to top.
+    fn iter_col<'a>(
+        &'a self,
+        col: usize,
+    ) -> impl DoubleEndedIterator<Item = &'a T> + ExactSizeIterator
+    where
+        Self: Sized,
+        T: 'a,
+    {
+        (0..self.rows()).map(move |row| self.get(row, col))
+    }
+
+    fn iter_rows<'a>(&'a self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &'a T>>
+    where
+        Self: Sized,
+        T: 'a,
+    {
+        (0..self.rows()).map(move |row| self.iter_row(row))
+    }
+
+    fn iter_cols<'a>(&'a self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &'a T>>
+    where
+        Self: Sized,
+        T: 'a,
+    {
+        (0..self.cols()).map(move |col| self.iter_col(col))
+    }
+
+    /// Diagonals running down and to the right, each from its top-left end.
+    ///
+    /// Starts with the bottom-left corner and ends with the top-right corner.
+    fn iter_diagonals<'a>(&'a self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &'a T>>
+    where
+        Self: Sized,
+        T: 'a,
+    {
+        let (rows, cols) = (self.rows(), self.cols());
+        let count = if rows == 0 || cols == 0 {
+            0
+        } else {
+            rows + cols - 1
+        };
+        (0..count).map(move |idx| {
+            let (start_row, start_col) = if idx < rows {
+                (rows - 1 - idx, 0)
+            } else {
+                (0, idx + 1 - rows)
+            };
+            let len = (rows - start_row).min(cols - start_col);
+            (0..len).map(move |step| self.get(start_row + step, start_col + step))
+        })
+    }
+
+    /// Diagonals running down and to the left, each from its top-right end.
+    ///
+    /// Starts with the top-left corner and ends with the bottom-right corner.
+    fn iter_anti_diagonals<'a>(
+        &'a self,
+    ) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &'a T>>
+    where
+        Self: Sized,
+        T: 'a,
+    {
+        let (rows, cols) = (self.rows(), self.cols());
+        let count = if rows == 0 || cols == 0 {
+            0
+        } else {
+            rows + cols - 1
+        };
+        (0..count).map(move |sum| {
+            let first_row = (sum + 1).saturating_sub(cols);
+            let last_row = sum.min(rows - 1);
+            (first_row..=last_row).map(move |row| self.get(row, sum - row))
+        })
+    }
+
+    /// Top-left positions where `pattern` occurs, in row-major order.
+    ///
+    /// Pattern cells for which `is_wildcard` holds match anything.
+    fn find_pattern<P, W>(&self, pattern: &P, is_wildcard: W) -> Vec<(usize, usize)>
+    where
+        Self: Sized,
+        P: Matrix<T> + ?Sized,
+        T: PartialEq,
+        W: Fn(&T) -> bool,
+    {
+        self.find_pattern_oriented(pattern, is_wildcard, &[Orientation::Identity])
+            .into_iter()
+            .map(|found| (found.row, found.col))
+            .collect()
+    }
+
+    /// Like `find_pattern`, trying the pattern under each of `orientations`
+    /// (e.g. `&Orientation::ALL`).
+    ///
+    /// Positions are always in this matrix's coordinates. A symmetric pattern is
+    /// reported once for every orientation that maps it onto the same cells.
+    fn find_pattern_oriented<P, W>(
+        &self,
+        pattern: &P,
+        is_wildcard: W,
+        orientations: &[Orientation],
+    ) -> Vec<PatternMatch>
+    where
+        Self: Sized,
+        P: Matrix<T> + ?Sized,
+        T: PartialEq,
+        W: Fn(&T) -> bool,
+    {
+        let mut found = Vec::new();
+        for &orientation in orientations {
+            let pattern = pattern.orient(orientation);
+            let (height, width) = (pattern.rows(), pattern.cols());
+            if height > self.rows() || width > self.cols() {
+                continue;
+            }
+            for row in 0..=self.rows() - height {
+                for col in 0..=self.cols() - width {
+                    let window = Sliced::slice(self, row..row + height, col..col + width);
+                    let matches = pattern.iter_indexed().all(|(r, c, expected)| {
+                        is_wildcard(expected) || expected == window.get(r, c)
+                    });
+                    if matches {
+                        found.push(PatternMatch {
+                            row,
+                            col,
+                            orientation,
+                        });
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    /// The same value for all 8 orientations of a matrix; see `Canonical`.
+    fn canonical(&self) -> Canonical<T>
+    where
+        Self: Sized,
+        T: Ord + Clone + Hash,
+    {
+        let best = self
+            .orientations()
+            .min_by(|a, b| {
+                (a.rows(), a.cols())
+                    .cmp(&(b.rows(), b.cols()))
+                    .then_with(|| a.iter_by_rows().cmp(b.iter_by_rows()))
+            })
+            .unwrap();
+        let grid = Grid::try_from(best.to_vec_vec()).unwrap();
+        let mut hasher = DefaultHasher::new();
+        grid.hash(&mut hasher);
+        Canonical {
+            grid,
+            orientation: best.orientation(),
+            fingerprint: hasher.finish(),
+        }
+    }
+}
+
+/// Orientation-invariant owned copy of a matrix, for deduplicating shapes or as a
+/// `bfs` state key up to rotation and reflection.
+///
+/// Holds the orientation that is smallest by shape and then row-major contents.
+/// Hashing only feeds a fingerprint computed once on creation, so repeated
+/// `HashSet` lookups don't rehash every cell.
+#[derive(Clone, Debug)]
+pub struct Canonical<T> {
+    grid: Grid<T>,
+    orientation: Orientation,
+    fingerprint: u64,
+}
+
+impl<T> Canonical<T> {
+    /// Orientation of the original matrix that gives this form.
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.grid
+    }
+}
+
+impl<T: PartialEq> PartialEq for Canonical<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.fingerprint == other.fingerprint && self.grid == other.grid
+    }
+}
+
+impl<T: Eq> Eq for Canonical<T> {}
+
+impl<T> Hash for Canonical<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.fingerprint);
+    }
 }
 
-impl<'a, T> Matrix<'a, T> for Vec<Vec<T>> {
+impl<T> Matrix<T> for Canonical<T> {
     fn get(&self, row: usize, col: usize) -> &T {
-        self.index(row).get(col).unwrap()
+        self.grid.get(row, col)
+    }
+
+    fn rows(&self) -> usize {
+        self.grid.rows()
+    }
+
+    fn cols(&self) -> usize {
+        self.grid.cols()
+    }
+}
+
+/// Occurrence of a pattern found by `Matrix::find_pattern_oriented`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PatternMatch {
+    pub row: usize,
+    pub col: usize,
+    /// Orientation applied to the pattern.
+    pub orientation: Orientation,
+}
+
+/// Checks that `rows` is rectangular, naming the first row whose length differs.
+///
+/// `Vec<Vec<T>>` reports the first row's length as `cols()`, so ragged input would
+/// otherwise give wrong results or panic deep inside a view.
+pub fn rectangular<T>(rows: Vec<Vec<T>>) -> Result<Vec<Vec<T>>> {
+    let cols = rows.first().map_or(0, Vec::len);
+    if let Some((idx, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != cols) {
+        return Err(anyhow!(
+            "Row {} has {} columns, expected {}",
+            idx,
+            row.len(),
+            cols
+        ));
+    }
+    Ok(rows)
+}
+
+fn out_of_bounds(row: usize, col: usize, rows: usize, cols: usize) -> ! {
+    panic!(
+        "({}, {}) out of bounds for {}x{} matrix",
+        row, col, rows, cols
+    )
+}
+
+impl<T> Matrix<T> for Vec<Vec<T>> {
+    fn get(&self, row: usize, col: usize) -> &T {
+        match Matrix::try_get(self, row, col) {
+            Some(value) => value,
+            None => out_of_bounds(row, col, self.rows(), self.cols()),
+        }
     }
 
     fn rows(&self) -> usize {
@@ -68,17 +446,235 @@ impl<'a, T> Matrix<'a, T> for Vec<Vec<T>> {
     }
 
     fn cols(&self) -> usize {
-        self[0].len()
+        self.first().map_or(0, Vec::len)
+    }
+
+    fn try_get(&self, row: usize, col: usize) -> Option<&T> {
+        self.as_slice().get(row)?.get(col)
     }
 }
 
-pub struct Identity<'a, M> {
-    matrix: &'a M,
+impl<T, M> Matrix<T> for &M
+where
+    M: Matrix<T> + ?Sized,
+{
+    fn get(&self, row: usize, col: usize) -> &T {
+        (**self).get(row, col)
+    }
+
+    fn rows(&self) -> usize {
+        (**self).rows()
+    }
+
+    fn cols(&self) -> usize {
+        (**self).cols()
+    }
+
+    fn try_get(&self, row: usize, col: usize) -> Option<&T> {
+        (**self).try_get(row, col)
+    }
 }
 
-impl<'a, T, M> Matrix<'a, T> for Identity<'a, M>
+impl<T, M> Matrix<T> for &mut M
 where
-    M: Matrix<'a, T>,
+    M: Matrix<T> + ?Sized,
+{
+    fn get(&self, row: usize, col: usize) -> &T {
+        (**self).get(row, col)
+    }
+
+    fn rows(&self) -> usize {
+        (**self).rows()
+    }
+
+    fn cols(&self) -> usize {
+        (**self).cols()
+    }
+
+    fn try_get(&self, row: usize, col: usize) -> Option<&T> {
+        (**self).try_get(row, col)
+    }
+}
+
+pub trait MatrixMut<T>: Matrix<T> {
+    fn get_mut(&mut self, row: usize, col: usize) -> &mut T;
+
+    fn try_get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        if row < self.rows() && col < self.cols() {
+            Some(self.get_mut(row, col))
+        } else {
+            None
+        }
+    }
+
+    fn get_signed_mut(&mut self, row: isize, col: isize) -> Option<&mut T> {
+        self.try_get_mut(usize::try_from(row).ok()?, usize::try_from(col).ok()?)
+    }
+
+    fn set(&mut self, row: usize, col: usize, value: T) {
+        *self.get_mut(row, col) = value;
+    }
+
+    fn flip_vertically_mut(&mut self) -> VerticallyFlippedMut<'_, Self>
+    where
+        Self: Sized,
+    {
+        VerticallyFlipped::wrap(self)
+    }
+
+    fn flip_horizontally_mut(&mut self) -> HorizontallyFlippedMut<'_, Self>
+    where
+        Self: Sized,
+    {
+        HorizontallyFlipped::wrap(self)
+    }
+
+    fn rotate_mut(&mut self) -> RotatedMut<'_, Self>
+    where
+        Self: Sized,
+    {
+        Rotated::wrap(self)
+    }
+
+    fn transpose_mut(&mut self) -> TransposedMut<'_, Self>
+    where
+        Self: Sized,
+    {
+        Transposed::wrap(self)
+    }
+
+    fn orient_mut(&mut self, orientation: Orientation) -> OrientedMut<'_, Self>
+    where
+        Self: Sized,
+    {
+        Oriented::wrap(self, orientation)
+    }
+
+    fn slice_mut(
+        &mut self,
+        rows_range: Range<usize>,
+        cols_range: Range<usize>,
+    ) -> SlicedMut<'_, Self>
+    where
+        Self: Sized,
+    {
+        Sliced::slice(self, rows_range, cols_range)
+    }
+
+    /// Rolls every movable cell towards `direction` until it hits the edge, a
+    /// blocking cell or another movable cell; all other cells count as empty.
+    ///
+    /// Takes `O(rows * cols)`. Returns whether anything moved, so callers can
+    /// tilt until stable.
+    fn tilt<F, G>(&mut self, direction: Direction, is_movable: F, is_blocking: G) -> bool
+    where
+        Self: Sized,
+        T: Clone,
+        F: Fn(&T) -> bool,
+        G: Fn(&T) -> bool,
+    {
+        let mut view = self.orient_mut(direction.orientation());
+        let mut moved = false;
+        for col in 0..view.cols() {
+            let mut free = 0;
+            for row in 0..view.rows() {
+                let cell = view.get(row, col);
+                if is_blocking(cell) {
+                    free = row + 1;
+                } else if is_movable(cell) {
+                    if free != row {
+                        let empty = view.get(free, col).clone();
+                        let moving = std::mem::replace(view.get_mut(row, col), empty);
+                        view.set(free, col, moving);
+                        moved = true;
+                    }
+                    free += 1;
+                }
+            }
+        }
+        moved
+    }
+
+    /// Rotates a row `by` places to the right (left if negative), wrapping around.
+    fn shift_row(&mut self, row: usize, by: isize)
+    where
+        Self: Sized,
+        T: Clone,
+    {
+        let cells = self.iter_row(row).cloned().collect::<Vec<_>>();
+        let offset = by.rem_euclid(cells.len().max(1) as isize) as usize;
+        for (col, value) in cells.into_iter().enumerate() {
+            self.set(row, (col + offset) % self.cols(), value);
+        }
+    }
+
+    /// Rotates a column `by` places down (up if negative), wrapping around.
+    fn shift_col(&mut self, col: usize, by: isize)
+    where
+        Self: Sized,
+        T: Clone,
+    {
+        self.transpose_mut().shift_row(col, by);
+    }
+}
+
+/// Direction for `MatrixMut::tilt`, with `Up` towards row 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Self::Up, Self::Down, Self::Left, Self::Right];
+
+    /// View orientation in which this direction points up.
+    fn orientation(self) -> Orientation {
+        match self {
+            Self::Up => Orientation::Identity,
+            Self::Down => Orientation::FlippedVertically,
+            Self::Left => Orientation::Transposed,
+            Self::Right => Orientation::AntiTransposed,
+        }
+    }
+}
+
+impl<T> MatrixMut<T> for Vec<Vec<T>> {
+    fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+        let (rows, cols) = (self.rows(), self.cols());
+        match MatrixMut::try_get_mut(self, row, col) {
+            Some(value) => value,
+            None => out_of_bounds(row, col, rows, cols),
+        }
+    }
+
+    fn try_get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.as_mut_slice().get_mut(row)?.get_mut(col)
+    }
+}
+
+impl<T, M> MatrixMut<T> for &mut M
+where
+    M: MatrixMut<T> + ?Sized,
+{
+    fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+        (**self).get_mut(row, col)
+    }
+
+    fn try_get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        (**self).try_get_mut(row, col)
+    }
+}
+
+pub struct Identity<M> {
+    matrix: M,
+}
+
+impl<T, M> Matrix<T> for Identity<M>
+where
+    M: Matrix<T>,
 {
     fn get(&self, row: usize, col: usize) -> &T {
         self.matrix.get(row, col)
@@ -93,13 +689,32 @@ where
     }
 }
 
-pub struct VerticallyFlipped<'a, M> {
-    matrix: &'a M,
+impl<T, M> MatrixMut<T> for Identity<M>
+where
+    M: MatrixMut<T>,
+{
+    fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+        self.matrix.get_mut(row, col)
+    }
 }
 
-impl<'a, T, M> Matrix<'a, T> for VerticallyFlipped<'a, M>
+impl<M> Identity<M> {
+    pub fn wrap(matrix: M) -> Self {
+        Self { matrix }
+    }
+
+    pub fn into_inner(self) -> M {
+        self.matrix
+    }
+}
+
+pub struct VerticallyFlipped<M> {
+    matrix: M,
+}
+
+impl<T, M> Matrix<T> for VerticallyFlipped<M>
 where
-    M: Matrix<'a, T>,
+    M: Matrix<T>,
 {
     fn get(&self, row: usize, col: usize) -> &T {
         self.matrix.get(self.matrix.rows() - row - 1, col)
@@ -114,22 +729,33 @@ where
     }
 }
 
-impl<'a, M> VerticallyFlipped<'a, M> {
-    pub fn wrap<T>(matrix: &'a M) -> Self
-    where
-        M: Matrix<'a, T>,
-    {
+impl<T, M> MatrixMut<T> for VerticallyFlipped<M>
+where
+    M: MatrixMut<T>,
+{
+    fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+        let row = self.matrix.rows() - row - 1;
+        self.matrix.get_mut(row, col)
+    }
+}
+
+impl<M> VerticallyFlipped<M> {
+    pub fn wrap(matrix: M) -> Self {
         Self { matrix }
     }
+
+    pub fn into_inner(self) -> M {
+        self.matrix
+    }
 }
 
-pub struct HorizontallyFlipped<'a, M> {
-    matrix: &'a M,
+pub struct HorizontallyFlipped<M> {
+    matrix: M,
 }
 
-impl<'a, T, M> Matrix<'a, T> for HorizontallyFlipped<'a, M>
+impl<T, M> Matrix<T> for HorizontallyFlipped<M>
 where
-    M: Matrix<'a, T>,
+    M: Matrix<T>,
 {
     fn get(&self, row: usize, col: usize) -> &T {
         self.matrix.get(row, self.matrix.cols() - col - 1)
@@ -144,25 +770,37 @@ where
     }
 }
 
-impl<'a, M> HorizontallyFlipped<'a, M> {
-    pub fn wrap<T>(matrix: &'a M) -> Self
-    where
-        M: Matrix<'a, T>,
-    {
+impl<T, M> MatrixMut<T> for HorizontallyFlipped<M>
+where
+    M: MatrixMut<T>,
+{
+    fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+        let col = self.matrix.cols() - col - 1;
+        self.matrix.get_mut(row, col)
+    }
+}
+
+impl<M> HorizontallyFlipped<M> {
+    pub fn wrap(matrix: M) -> Self {
         Self { matrix }
     }
+
+    pub fn into_inner(self) -> M {
+        self.matrix
+    }
 }
 
-pub struct Rotated<'a, M> {
-    matrix: &'a M,
+/// Counter-clockwise rotation by 90 degrees.
+pub struct Rotated<M> {
+    matrix: M,
 }
 
-impl<'a, T, M> Matrix<'a, T> for Rotated<'a, M>
+impl<T, M> Matrix<T> for Rotated<M>
 where
-    M: Matrix<'a, T>,
+    M: Matrix<T>,
 {
     fn get(&self, row: usize, col: usize) -> &T {
-        self.matrix.get(col, self.matrix.rows() - row - 1)
+        self.matrix.get(col, self.matrix.cols() - row - 1)
     }
 
     fn rows(&self) -> usize {
@@ -174,24 +812,35 @@ where
     }
 }
 
-impl<'a, M> Rotated<'a, M> {
-    pub fn wrap<T>(matrix: &'a M) -> Self
-    where
-        M: Matrix<'a, T>,
-    {
+impl<T, M> MatrixMut<T> for Rotated<M>
+where
+    M: MatrixMut<T>,
+{
+    fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+        let inner_col = self.matrix.cols() - row - 1;
+        self.matrix.get_mut(col, inner_col)
+    }
+}
+
+impl<M> Rotated<M> {
+    pub fn wrap(matrix: M) -> Self {
         Self { matrix }
     }
+
+    pub fn into_inner(self) -> M {
+        self.matrix
+    }
 }
 
-pub struct Sliced<'a, M> {
-    matrix: &'a M,
+pub struct Sliced<M> {
+    matrix: M,
     rows_range: Range<usize>,
     cols_range: Range<usize>,
 }
 
-impl<'a, T, M> Matrix<'a, T> for Sliced<'a, M>
+impl<T, M> Matrix<T> for Sliced<M>
 where
-    M: Matrix<'a, T>,
+    M: Matrix<T>,
 {
     fn get(&self, row: usize, col: usize) -> &T {
         self.matrix
@@ -207,26 +856,214 @@ where
     }
 }
 
-impl<'a, M> Sliced<'a, M> {
-    pub fn slice<T>(matrix: &'a M, rows_range: Range<usize>, cols_range: Range<usize>) -> Self
-    where
-        M: Matrix<'a, T>,
-    {
+impl<T, M> MatrixMut<T> for Sliced<M>
+where
+    M: MatrixMut<T>,
+{
+    fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+        self.matrix
+            .get_mut(self.rows_range.start + row, self.cols_range.start + col)
+    }
+}
+
+impl<M> Sliced<M> {
+    pub fn slice(matrix: M, rows_range: Range<usize>, cols_range: Range<usize>) -> Self {
         Self {
             matrix,
             rows_range,
             cols_range,
         }
     }
+
+    pub fn into_inner(self) -> M {
+        self.matrix
+    }
+}
+
+/// One of the 8 rotations and reflections of a matrix (the dihedral group of the square).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Orientation {
+    Identity,
+    /// Same as `Matrix::rotate`.
+    RotatedCounterClockwise,
+    Rotated180,
+    RotatedClockwise,
+    FlippedVertically,
+    FlippedHorizontally,
+    Transposed,
+    /// Reflection across the anti-diagonal.
+    AntiTransposed,
+}
+
+impl Orientation {
+    pub const ALL: [Orientation; 8] = [
+        Self::Identity,
+        Self::RotatedCounterClockwise,
+        Self::Rotated180,
+        Self::RotatedClockwise,
+        Self::FlippedVertically,
+        Self::FlippedHorizontally,
+        Self::Transposed,
+        Self::AntiTransposed,
+    ];
+
+    /// `(transpose, flip_rows, flip_cols)` where the flips apply to the inner matrix
+    /// coordinates after transposing.
+    fn parts(self) -> (bool, bool, bool) {
+        match self {
+            Self::Identity => (false, false, false),
+            Self::RotatedCounterClockwise => (true, false, true),
+            Self::Rotated180 => (false, true, true),
+            Self::RotatedClockwise => (true, true, false),
+            Self::FlippedVertically => (false, true, false),
+            Self::FlippedHorizontally => (false, false, true),
+            Self::Transposed => (true, false, false),
+            Self::AntiTransposed => (true, true, true),
+        }
+    }
+
+    /// Whether rows and columns swap, i.e. whether a `R`x`C` matrix becomes `C`x`R`.
+    pub fn is_transposing(self) -> bool {
+        self.parts().0
+    }
+
+    /// Maps a position in the oriented view to the position in a `rows`x`cols` inner matrix.
+    pub fn to_inner(self, row: usize, col: usize, rows: usize, cols: usize) -> (usize, usize) {
+        let (transpose, flip_rows, flip_cols) = self.parts();
+        let (row, col) = if transpose { (col, row) } else { (row, col) };
+        (
+            if flip_rows { rows - row - 1 } else { row },
+            if flip_cols { cols - col - 1 } else { col },
+        )
+    }
+}
+
+pub struct Transposed<M> {
+    matrix: M,
+}
+
+impl<T, M> Matrix<T> for Transposed<M>
+where
+    M: Matrix<T>,
+{
+    fn get(&self, row: usize, col: usize) -> &T {
+        self.matrix.get(col, row)
+    }
+
+    fn rows(&self) -> usize {
+        self.matrix.cols()
+    }
+
+    fn cols(&self) -> usize {
+        self.matrix.rows()
+    }
+}
+
+impl<T, M> MatrixMut<T> for Transposed<M>
+where
+    M: MatrixMut<T>,
+{
+    fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+        self.matrix.get_mut(col, row)
+    }
+}
+
+impl<M> Transposed<M> {
+    pub fn wrap(matrix: M) -> Self {
+        Self { matrix }
+    }
+
+    pub fn into_inner(self) -> M {
+        self.matrix
+    }
+}
+
+pub struct Oriented<M> {
+    matrix: M,
+    orientation: Orientation,
 }
 
-pub struct MatrixIterator<'a, T> {
-    matrix: &'a dyn Matrix<'a, T>,
+impl<M> Oriented<M> {
+    pub fn wrap(matrix: M, orientation: Orientation) -> Self {
+        Self {
+            matrix,
+            orientation,
+        }
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    pub fn into_inner(self) -> M {
+        self.matrix
+    }
+
+    fn to_inner<T>(&self, row: usize, col: usize) -> (usize, usize)
+    where
+        M: Matrix<T>,
+    {
+        self.orientation
+            .to_inner(row, col, self.matrix.rows(), self.matrix.cols())
+    }
+}
+
+impl<T, M> Matrix<T> for Oriented<M>
+where
+    M: Matrix<T>,
+{
+    fn get(&self, row: usize, col: usize) -> &T {
+        let (row, col) = self.to_inner(row, col);
+        self.matrix.get(row, col)
+    }
+
+    fn rows(&self) -> usize {
+        if self.orientation.is_transposing() {
+            self.matrix.cols()
+        } else {
+            self.matrix.rows()
+        }
+    }
+
+    fn cols(&self) -> usize {
+        if self.orientation.is_transposing() {
+            self.matrix.rows()
+        } else {
+            self.matrix.cols()
+        }
+    }
+}
+
+impl<T, M> MatrixMut<T> for Oriented<M>
+where
+    M: MatrixMut<T>,
+{
+    fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+        let (row, col) = self.to_inner(row, col);
+        self.matrix.get_mut(row, col)
+    }
+}
+
+pub type IdentityMut<'a, M> = Identity<&'a mut M>;
+pub type VerticallyFlippedMut<'a, M> = VerticallyFlipped<&'a mut M>;
+pub type HorizontallyFlippedMut<'a, M> = HorizontallyFlipped<&'a mut M>;
+pub type RotatedMut<'a, M> = Rotated<&'a mut M>;
+pub type SlicedMut<'a, M> = Sliced<&'a mut M>;
+pub type TransposedMut<'a, M> = Transposed<&'a mut M>;
+pub type OrientedMut<'a, M> = Oriented<&'a mut M>;
+
+pub struct MatrixIterator<'a, M: ?Sized, T> {
+    matrix: &'a M,
     row: usize,
     col: usize,
+    _phantom: PhantomData<&'a T>,
 }
 
-impl<'a, T> Iterator for MatrixIterator<'a, T> {
+impl<'a, M, T> Iterator for MatrixIterator<'a, M, T>
+where
+    M: Matrix<T> + ?Sized,
+    T: 'a,
+{
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -279,6 +1116,14 @@ mod tests {
         assert_eq!(r.get(0, 0), &3);
         assert_eq!(r.get(1, 0), &2);
         assert_eq!(r.get(2, 1), &4);
+
+        let wide = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let r = Rotated { matrix: &wide };
+        assert_eq!((r.rows(), r.cols()), (3, 2));
+        assert_eq!(r.get(0, 0), &3);
+        assert_eq!(r.get(0, 1), &6);
+        assert_eq!(r.get(2, 0), &1);
+        assert_eq!(r.to_vec_vec(), vec![vec![3, 6], vec![2, 5], vec![1, 4]]);
     }
 
     #[test]
@@ -308,4 +1153,375 @@ mod tests {
         assert_eq!(iter.next(), None);
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn test_set() {
+        let mut m = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        m.set(1, 2, 42);
+        *m.get_mut(0, 0) += 10;
+        assert_eq!(m, vec![vec![11, 2, 3], vec![4, 5, 42]]);
+    }
+
+    #[test]
+    fn test_mut_views() {
+        let mut m = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+
+        VerticallyFlippedMut::wrap(&mut m).set(0, 0, 70);
+        HorizontallyFlippedMut::wrap(&mut m).set(0, 0, 30);
+        RotatedMut::wrap(&mut m).set(2, 1, 40);
+        assert_eq!(m, vec![vec![1, 2, 30], vec![40, 5, 6], vec![70, 8, 9]]);
+
+        let mut s = m.slice_mut(1..3, 1..3);
+        assert_eq!(s.get(0, 0), &5);
+        s.set(1, 1, 0);
+        assert_eq!(m[2][2], 0);
+    }
+
+    #[test]
+    fn test_fill_rotated_sub_region() {
+        let mut m = vec![vec![0; 3]; 2];
+        let mut r = RotatedMut::wrap(&mut m);
+        assert_eq!((r.rows(), r.cols()), (3, 2));
+        let mut value = 1;
+        for row in 0..r.rows() {
+            for col in 0..r.cols() {
+                r.set(row, col, value);
+                value += 1;
+            }
+        }
+        assert_eq!(r.to_vec_vec(), vec![vec![1, 2], vec![3, 4], vec![5, 6]]);
+        assert_eq!(m, vec![vec![5, 3, 1], vec![6, 4, 2]]);
+    }
+
+    #[test]
+    fn test_transposed() {
+        let m = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        assert_eq!(
+            m.transpose().to_vec_vec(),
+            vec![vec![1, 4], vec![2, 5], vec![3, 6]]
+        );
+    }
+
+    #[test]
+    fn test_orientations() {
+        let m = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        assert_eq!(
+            (&m).rotate_clockwise().to_vec_vec(),
+            vec![vec![4, 1], vec![5, 2], vec![6, 3]]
+        );
+        assert_eq!(
+            (&m).rotate_180().to_vec_vec(),
+            vec![vec![6, 5, 4], vec![3, 2, 1]]
+        );
+        assert_eq!(
+            (&m).orient(Orientation::RotatedCounterClockwise)
+                .to_vec_vec(),
+            (&m).rotate().to_vec_vec()
+        );
+        assert_eq!(
+            (&m).orient(Orientation::FlippedVertically).to_vec_vec(),
+            (&m).flip_vertically().to_vec_vec()
+        );
+        assert_eq!(
+            (&m).orient(Orientation::FlippedHorizontally).to_vec_vec(),
+            (&m).flip_horizontally().to_vec_vec()
+        );
+        assert_eq!(
+            (&m).orient(Orientation::Transposed).to_vec_vec(),
+            (&m).transpose().to_vec_vec()
+        );
+        assert_eq!(
+            (&m).orient(Orientation::AntiTransposed).to_vec_vec(),
+            vec![vec![6, 3], vec![5, 2], vec![4, 1]]
+        );
+
+        let all = m
+            .orientations()
+            .map(|o| o.to_vec_vec())
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(all.len(), 8);
+        assert_eq!(
+            m.orientations()
+                .map(|o| o.orientation())
+                .collect::<Vec<_>>(),
+            Orientation::ALL.to_vec()
+        );
+    }
+
+    #[test]
+    fn test_oriented_mut() {
+        let mut m = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        m.orient_mut(Orientation::RotatedClockwise).set(0, 0, 40);
+        m.transpose_mut().set(2, 0, 30);
+        assert_eq!(m, vec![vec![1, 2, 30], vec![40, 5, 6]]);
+    }
+
+    #[test]
+    fn test_chained_views() {
+        let m = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+
+        let twice = (&m).rotate().rotate();
+        assert_eq!(twice.to_vec_vec(), (&m).rotate_180().to_vec_vec());
+
+        let chained = (&m).flip_vertically().slice(0..2, 1..3).transpose();
+        assert_eq!(
+            chained.iter_by_rows().copied().collect::<Vec<_>>(),
+            vec![8, 5, 9, 6]
+        );
+
+        let owned = m.clone().rotate_clockwise().flip_horizontally();
+        assert_eq!(owned.to_vec_vec(), (&m).transpose().to_vec_vec());
+        assert_eq!(owned.into_inner().into_inner(), m);
+    }
+
+    #[test]
+    fn test_chained_mut_views() {
+        let mut m = vec![vec![0; 4]; 3];
+        {
+            let mut view = (&mut m).slice(1..3, 1..4).rotate().flip_vertically();
+            assert_eq!((view.rows(), view.cols()), (3, 2));
+            view.set(0, 0, 1);
+            view.set(2, 1, 2);
+        }
+        assert_eq!(
+            m,
+            vec![vec![0, 0, 0, 0], vec![0, 1, 0, 0], vec![0, 0, 0, 2]]
+        );
+    }
+
+    #[test]
+    fn test_checked_access() {
+        let mut m = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        assert_eq!(m.try_get(1, 2), Some(&6));
+        assert_eq!(m.try_get(2, 0), None);
+        assert_eq!(m.try_get(0, 3), None);
+        assert_eq!(m.get_signed(-1, 0), None);
+        assert_eq!(m.get_signed(1, 1), Some(&5));
+        assert_eq!((&m).rotate().try_get(2, 1), Some(&4));
+        assert_eq!((&m).rotate().try_get(1, 2), None);
+
+        *m.get_signed_mut(0, 0).unwrap() = 10;
+        assert!(m.get_signed_mut(0, -1).is_none());
+        assert_eq!(m[0][0], 10);
+
+        let empty: Vec<Vec<u8>> = vec![];
+        assert_eq!((empty.rows(), empty.cols()), (0, 0));
+        assert_eq!(empty.try_get(0, 0), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "(2, 0) out of bounds for 2x3 matrix")]
+    fn test_out_of_bounds_message() {
+        let m = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        m.get(2, 0);
+    }
+
+    #[test]
+    fn test_rectangular() {
+        assert!(rectangular(vec![vec![1, 2], vec![3, 4]]).is_ok());
+        assert!(rectangular(Vec::<Vec<u8>>::new()).is_ok());
+        let error = rectangular(vec![vec![1, 2], vec![3, 4], vec![5]]).unwrap_err();
+        assert_eq!(error.to_string(), "Row 2 has 1 columns, expected 2");
+
+        let ragged = vec![vec![1, 2], vec![3]];
+        assert_eq!(ragged.try_get(1, 1), None);
+    }
+
+    #[test]
+    fn test_lines() {
+        let m = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        assert_eq!(m.iter_row(1).copied().collect::<Vec<_>>(), vec![4, 5, 6]);
+        assert_eq!(m.iter_col(2).rev().copied().collect::<Vec<_>>(), vec![6, 3]);
+        assert_eq!(m.iter_row(0).len(), 3);
+
+        let cols = m
+            .iter_cols()
+            .map(|col| col.copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(cols, vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+        assert_eq!(
+            (&m).transpose()
+                .iter_rows()
+                .map(|row| row.count())
+                .sum::<usize>(),
+            6
+        );
+    }
+
+    #[test]
+    fn test_diagonals() {
+        let m = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let diagonals = m
+            .iter_diagonals()
+            .map(|diagonal| diagonal.copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(diagonals, vec![vec![4], vec![1, 5], vec![2, 6], vec![3]]);
+
+        let anti_diagonals = m
+            .iter_anti_diagonals()
+            .map(|diagonal| diagonal.copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            anti_diagonals,
+            vec![vec![1], vec![2, 4], vec![3, 5], vec![6]]
+        );
+
+        let empty: Vec<Vec<u8>> = vec![];
+        assert_eq!(empty.iter_diagonals().count(), 0);
+        assert_eq!(empty.iter_anti_diagonals().count(), 0);
+        assert_eq!((&m).slice(0..0, 0..3).iter_anti_diagonals().count(), 0);
+    }
+
+    #[test]
+    fn test_indexed_and_positions() {
+        let m = vec![vec!['#', '.'], vec!['.', '#']];
+        assert_eq!(m.iter_indexed().nth(2), Some((1, 0, &'.')));
+        assert_eq!(
+            m.positions(|&c| c == '#').collect::<Vec<_>>(),
+            vec![(0, 0), (1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_find_pattern() {
+        let m = ["#.#.", ".#..", "#.#.", "...#"]
+            .iter()
+            .map(|line| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let cross = vec![
+            vec!['#', '?', '#'],
+            vec!['?', '#', '?'],
+            vec!['#', '?', '#'],
+        ];
+        assert_eq!(m.find_pattern(&cross, |&c| c == '?'), vec![(0, 0)]);
+        assert_eq!(
+            m.find_pattern(&vec![vec!['#', '.']], |_| false),
+            vec![(0, 0), (0, 2), (1, 1), (2, 0), (2, 2)]
+        );
+        assert!(m.find_pattern(&vec![vec!['.'; 5]], |_| false).is_empty());
+
+        let corner = vec![vec!['#', '.'], vec!['.', '.']];
+        let found = m.find_pattern_oriented(&corner, |_| false, &Orientation::ALL);
+        let at_1_2 = found
+            .iter()
+            .filter(|found| (found.row, found.col) == (1, 2))
+            .map(|found| found.orientation)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            at_1_2,
+            vec![
+                Orientation::RotatedCounterClockwise,
+                Orientation::FlippedVertically
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_non_square_pattern_in_all_orientations() {
+        let m = vec![vec![0, 1, 0], vec![0, 2, 0], vec![0, 3, 0]];
+        let pattern = vec![vec![1, 2, 3]];
+        assert!(m.find_pattern(&pattern, |_| false).is_empty());
+        let found = m.find_pattern_oriented(&pattern, |_| false, &Orientation::ALL);
+        let orientations = found
+            .iter()
+            .map(|found| (found.row, found.col, found.orientation))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            orientations,
+            vec![
+                (0, 1, Orientation::RotatedClockwise),
+                (0, 1, Orientation::Transposed)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_canonical() {
+        let m = vec![vec![3, 1, 2], vec![6, 5, 4]];
+        let canonical = m.canonical();
+        assert_eq!(
+            canonical.clone().into_grid().into_vec_vec(),
+            vec![vec![2, 1, 3], vec![4, 5, 6]]
+        );
+        assert_eq!(canonical.orientation(), Orientation::FlippedHorizontally);
+        assert_eq!((canonical.rows(), canonical.cols()), (2, 3));
+        assert_eq!(canonical.get(0, 0), &2);
+
+        let shapes = m
+            .orientations()
+            .map(|oriented| oriented.to_vec_vec().canonical())
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(shapes.len(), 1);
+        assert!(shapes.contains(&canonical));
+        assert!(!shapes.contains(&vec![vec![3, 1, 2], vec![6, 4, 5]].canonical()));
+        assert_eq!(
+            (&m).rotate().canonical().fingerprint(),
+            canonical.fingerprint()
+        );
+    }
+
+    #[test]
+    fn test_tilt() {
+        let mut m = ["O.#.", "..O.", "O#..", ".O.O"]
+            .iter()
+            .map(|line| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let tilt =
+            |m: &mut Vec<Vec<char>>, direction| m.tilt(direction, |&c| c == 'O', |&c| c == '#');
+        let show = |m: &Vec<Vec<char>>| m.render(|&c| c).to_string();
+
+        assert!(tilt(&mut m, Direction::Up));
+        assert_eq!(show(&m), "O.#O\nO.O.\n.#..\n.O..");
+        assert!(!tilt(&mut m, Direction::Up));
+        tilt(&mut m, Direction::Right);
+        assert_eq!(show(&m), ".O#O\n..OO\n.#..\n...O");
+        tilt(&mut m, Direction::Down);
+        assert_eq!(show(&m), "..#.\n.O.O\n.#.O\n..OO");
+        tilt(&mut m, Direction::Left);
+        assert_eq!(show(&m), "..#.\nOO..\n.#O.\nOO..");
+    }
+
+    #[test]
+    fn test_tilt_tile_map() {
+        let mut tile_map = crate::tile_map::TileMap::from(vec![vec![0, 1], vec![1, 0]]);
+        assert!(tile_map.tilt(Direction::Left, |&tile| tile == 1, |_| false));
+        assert_eq!(Vec::from(tile_map), vec![vec![1, 0], vec![1, 0]]);
+    }
+
+    #[test]
+    fn test_dyn_matrix() {
+        let m = vec![vec![1u8, 2], vec![3, 4]];
+        let d: &dyn Matrix<u8> = &m;
+        assert_eq!((d.rows(), d.cols(), d.get(1, 0)), (2, 2, &3));
+        assert_eq!(
+            (&d).iter_by_rows().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(d.rotate().to_vec_vec(), vec![vec![2, 4], vec![1, 3]]);
+        assert_eq!(
+            crate::algebra::add(d, d, crate::algebra::Arithmetic::Unchecked)
+                .unwrap()
+                .into_vec_vec(),
+            vec![vec![2, 4], vec![6, 8]]
+        );
+
+        let mut owned = m.clone();
+        let d: &mut dyn MatrixMut<u8> = &mut owned;
+        d.set(0, 0, 9);
+        d.transpose().set(0, 1, 7);
+        assert_eq!(owned, vec![vec![9, 2], vec![7, 4]]);
+    }
+
+    #[test]
+    fn test_shift() {
+        let mut m = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        m.shift_row(0, 1);
+        assert_eq!(m, vec![vec![3, 1, 2], vec![4, 5, 6]]);
+        m.shift_row(1, -4);
+        assert_eq!(m, vec![vec![3, 1, 2], vec![5, 6, 4]]);
+        m.shift_col(2, 1);
+        assert_eq!(m, vec![vec![3, 1, 4], vec![5, 6, 2]]);
+        m.shift_col(0, 0);
+        assert_eq!(m, vec![vec![3, 1, 4], vec![5, 6, 2]]);
+    }
 }
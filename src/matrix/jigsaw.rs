@@ -0,0 +1,347 @@
+//! Reassembling an image from square tiles that were shuffled, rotated and flipped,
+//! where neighbouring tiles have identical borders.
+
+use super::{Matrix, Orientation};
+use crate::grid::Grid;
+use anyhow::{anyhow, Result};
+use std::{collections::HashMap, hash::Hash};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+impl Side {
+    pub const ALL: [Side; 4] = [Self::Top, Self::Right, Self::Bottom, Self::Left];
+}
+
+/// Cells along one side, read left to right for `Top`/`Bottom` and top to bottom
+/// for `Left`/`Right`, so that touching sides of neighbours compare equal.
+///
+/// Empty for a tile without rows or columns.
+pub fn edge<T, M>(tile: &M, side: Side) -> Vec<T>
+where
+    T: Clone,
+    M: Matrix<T> + ?Sized,
+{
+    if tile.rows() == 0 || tile.cols() == 0 {
+        return Vec::new();
+    }
+    match side {
+        Side::Top => (&tile).iter_row(0).cloned().collect(),
+        Side::Bottom => (&tile).iter_row(tile.rows() - 1).cloned().collect(),
+        Side::Left => (&tile).iter_col(0).cloned().collect(),
+        Side::Right => (&tile).iter_col(tile.cols() - 1).cloned().collect(),
+    }
+}
+
+/// Tile (by index into the input) and the orientation it is used in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Placement {
+    pub tile: usize,
+    pub orientation: Orientation,
+}
+
+/// Looks up which oriented tiles have a given edge on a given side.
+pub struct EdgeIndex<T> {
+    by_side: [HashMap<Vec<T>, Vec<Placement>>; 4],
+}
+
+impl<T: Clone + Eq + Hash> EdgeIndex<T> {
+    pub fn new<M: Matrix<T>>(tiles: &[M]) -> Self {
+        let mut by_side: [HashMap<Vec<T>, Vec<Placement>>; 4] = Default::default();
+        for (idx, tile) in tiles.iter().enumerate() {
+            for oriented in tile.orientations() {
+                let placement = Placement {
+                    tile: idx,
+                    orientation: oriented.orientation(),
+                };
+                for (side, map) in Side::ALL.into_iter().zip(by_side.iter_mut()) {
+                    map.entry(edge(&oriented, side))
+                        .or_default()
+                        .push(placement);
+                }
+            }
+        }
+        Self { by_side }
+    }
+
+    /// Oriented tiles whose `side` reads exactly `edge`.
+    pub fn matching(&self, side: Side, edge: &[T]) -> &[Placement] {
+        self.by_side[side as usize]
+            .get(edge)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Number of distinct other tiles sharing `edge` in some orientation; corner
+    /// tiles are the ones with two sides where this is zero.
+    pub fn neighbours(&self, tile: usize, edge: &[T]) -> usize {
+        let mut others = self
+            .matching(Side::Top, edge)
+            .iter()
+            .map(|placement| placement.tile)
+            .filter(|&other| other != tile)
+            .collect::<Vec<_>>();
+        others.sort_unstable();
+        others.dedup();
+        others.len()
+    }
+}
+
+/// Square arrangement of tiles found by `solve`.
+pub struct Jigsaw<'a, M> {
+    tiles: &'a [M],
+    placements: Grid<Placement>,
+}
+
+/// Arranges `tiles` into a square so that all touching borders match.
+///
+/// Fails unless the tiles are equally sized squares whose count is a perfect square,
+/// or if no arrangement exists.
+pub fn solve<T, M>(tiles: &[M]) -> Result<Jigsaw<'_, M>>
+where
+    T: Clone + Eq + Hash,
+    M: Matrix<T>,
+{
+    let size = tiles.first().map_or(0, Matrix::rows);
+    if let Some(idx) = tiles
+        .iter()
+        .position(|tile| (tile.rows(), tile.cols()) != (size, size))
+    {
+        return Err(anyhow!(
+            "Tile {} is {}x{}, expected {}x{}",
+            idx,
+            tiles[idx].rows(),
+            tiles[idx].cols(),
+            size,
+            size
+        ));
+    }
+    if size == 0 {
+        return Err(anyhow!("No tiles to assemble"));
+    }
+    let side = tiles.len().isqrt();
+    if side * side != tiles.len() {
+        return Err(anyhow!("{} tiles don't form a square", tiles.len()));
+    }
+    let index = EdgeIndex::new(tiles);
+    let mut solver = Solver {
+        tiles,
+        index,
+        side,
+        used: vec![false; tiles.len()],
+        placed: Vec::with_capacity(tiles.len()),
+    };
+    if !solver.place() {
+        return Err(anyhow!("Tiles can't be assembled"));
+    }
+    Ok(Jigsaw {
+        tiles,
+        placements: Grid::from_cells(side, solver.placed)?,
+    })
+}
+
+struct Solver<'a, T, M> {
+    tiles: &'a [M],
+    index: EdgeIndex<T>,
+    side: usize,
+    used: Vec<bool>,
+    placed: Vec<Placement>,
+}
+
+impl<T, M> Solver<'_, T, M>
+where
+    T: Clone + Eq + Hash,
+    M: Matrix<T>,
+{
+    fn edge(&self, placement: Placement, side: Side) -> Vec<T> {
+        edge(
+            &(&self.tiles[placement.tile]).orient(placement.orientation),
+            side,
+        )
+    }
+
+    /// Fills the next position in row-major order, backtracking on dead ends.
+    fn place(&mut self) -> bool {
+        let position = self.placed.len();
+        if position == self.tiles.len() {
+            return true;
+        }
+        let (row, col) = (position / self.side, position % self.side);
+        let above = (row > 0).then(|| self.edge(self.placed[position - self.side], Side::Bottom));
+        let left = (col > 0).then(|| self.edge(self.placed[position - 1], Side::Right));
+        let candidates = match (&left, &above) {
+            (Some(left), _) => self.index.matching(Side::Left, left).to_vec(),
+            (None, Some(above)) => self.index.matching(Side::Top, above).to_vec(),
+            (None, None) => (0..self.tiles.len())
+                .flat_map(|tile| {
+                    Orientation::ALL
+                        .into_iter()
+                        .map(move |orientation| Placement { tile, orientation })
+                })
+                .collect(),
+        };
+        for candidate in candidates {
+            if self.used[candidate.tile] {
+                continue;
+            }
+            if let (Some(_), Some(above)) = (&left, &above) {
+                if &self.edge(candidate, Side::Top) != above {
+                    continue;
+                }
+            }
+            self.used[candidate.tile] = true;
+            self.placed.push(candidate);
+            if self.place() {
+                return true;
+            }
+            self.placed.pop();
+            self.used[candidate.tile] = false;
+        }
+        false
+    }
+}
+
+impl<M> Jigsaw<'_, M> {
+    /// Where each tile went, `side`x`side` in the layout of the assembled image.
+    pub fn placements(&self) -> &Grid<Placement> {
+        &self.placements
+    }
+
+    /// Tile indices at the top-left, top-right, bottom-left and bottom-right.
+    pub fn corners(&self) -> [usize; 4] {
+        let last = self.placements.rows() - 1;
+        [(0, 0), (0, last), (last, 0), (last, last)].map(|position| self.placements[position].tile)
+    }
+
+    /// The assembled image, dropping the outermost ring of every tile if
+    /// `strip_borders` is set.
+    pub fn image<T>(&self, strip_borders: bool) -> Grid<T>
+    where
+        T: Clone,
+        M: Matrix<T>,
+    {
+        let size = self.tiles[0].rows();
+        let margin = usize::from(strip_borders);
+        let inner = size.saturating_sub(2 * margin);
+        let side = self.placements.rows();
+        let mut cells = Vec::with_capacity(side * inner * side * inner);
+        for tile_row in 0..side {
+            let tiles = self
+                .placements
+                .row(tile_row)
+                .iter()
+                .map(|placement| {
+                    (&self.tiles[placement.tile])
+                        .orient(placement.orientation)
+                        .slice(margin..size - margin, margin..size - margin)
+                })
+                .collect::<Vec<_>>();
+            for row in 0..inner {
+                for tile in &tiles {
+                    cells.extend(tile.iter_row(row).cloned());
+                }
+            }
+        }
+        Grid::from_cells(side * inner, cells).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `side`x`side` tiles of `size`x`size` cut from one pseudo-random image,
+    /// with neighbours sharing their border row or column.
+    fn cut(side: usize, size: usize) -> (Vec<Vec<u32>>, Vec<Vec<Vec<u32>>>) {
+        let width = side * (size - 1) + 1;
+        let mut state = 12345u32;
+        let image = (0..width)
+            .map(|_| {
+                (0..width)
+                    .map(|_| {
+                        state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                        state >> 16
+                    })
+                    .collect()
+            })
+            .collect::<Vec<Vec<u32>>>();
+        let tiles = (0..side * side)
+            .map(|idx| {
+                let (row, col) = (idx / side * (size - 1), idx % side * (size - 1));
+                (&image)
+                    .slice(row..row + size, col..col + size)
+                    .to_vec_vec()
+            })
+            .collect();
+        (image, tiles)
+    }
+
+    #[test]
+    fn test_edges() {
+        let tile = vec![vec![1, 2], vec![3, 4]];
+        assert_eq!(edge(&tile, Side::Top), vec![1, 2]);
+        assert_eq!(edge(&tile, Side::Right), vec![2, 4]);
+        assert_eq!(edge(&tile, Side::Bottom), vec![3, 4]);
+        assert_eq!(edge(&tile, Side::Left), vec![1, 3]);
+        for side in Side::ALL {
+            assert!(edge(&Vec::<Vec<u8>>::new(), side).is_empty());
+            assert!(edge(&vec![Vec::<u8>::new(); 2], side).is_empty());
+        }
+
+        let index = EdgeIndex::new(&[tile]);
+        assert_eq!(
+            index.matching(Side::Left, &[2, 1]),
+            &[Placement {
+                tile: 0,
+                orientation: Orientation::RotatedCounterClockwise
+            }]
+        );
+        assert!(index.matching(Side::Top, &[1, 4]).is_empty());
+        assert_eq!(index.neighbours(0, &[1, 2]), 0);
+    }
+
+    #[test]
+    fn test_solve() {
+        let (image, tiles) = cut(3, 5);
+        let scrambled = tiles
+            .iter()
+            .rev()
+            .zip(Orientation::ALL.into_iter().cycle().skip(3))
+            .map(|(tile, orientation)| tile.orient(orientation).to_vec_vec())
+            .collect::<Vec<_>>();
+
+        let jigsaw = solve(&scrambled).unwrap();
+        let mut corners = jigsaw.corners();
+        corners.sort_unstable();
+        assert_eq!(corners, [0, 2, 6, 8]);
+
+        let full = jigsaw.image(false);
+        assert_eq!((full.rows(), full.cols()), (15, 15));
+
+        let stripped = jigsaw.image(true);
+        let expected = (0..9)
+            .map(|row| {
+                (0..9)
+                    .map(|col| image[row / 3 * 4 + 1 + row % 3][col / 3 * 4 + 1 + col % 3])
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert!(expected
+            .orientations()
+            .any(|oriented| oriented.to_vec_vec() == stripped.clone().into_vec_vec()));
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        let (_, mut tiles) = cut(2, 3);
+        assert!(solve(&tiles[..3]).is_err());
+        tiles[1][0][0] = u32::MAX;
+        assert!(solve(&tiles).is_err());
+        tiles[1].pop();
+        assert!(solve(&tiles).is_err());
+    }
+}
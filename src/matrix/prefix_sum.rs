@@ -0,0 +1,159 @@
+//! Summed-area tables for constant time rectangle sums.
+
+use super::Matrix;
+use crate::grid::Grid;
+use anyhow::{anyhow, Result};
+use std::ops::Range;
+
+/// Square window found by `PrefixSums::best_window`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Window {
+    pub row: usize,
+    pub col: usize,
+    pub size: usize,
+    pub sum: i128,
+}
+
+/// Sums of every top-left aligned rectangle of a numeric matrix.
+pub struct PrefixSums {
+    /// `(rows + 1)`x`(cols + 1)`, with `sums[(r, c)]` the sum of `0..r` x `0..c`.
+    sums: Grid<i128>,
+}
+
+impl PrefixSums {
+    /// Fails if a value doesn't fit in an `i128` or a running sum overflows.
+    pub fn new<T, M>(matrix: &M) -> Result<Self>
+    where
+        T: Copy + TryInto<i128>,
+        M: Matrix<T> + ?Sized,
+    {
+        let mut sums = Grid::new(matrix.rows() + 1, matrix.cols() + 1, 0);
+        for (row, col, &value) in (&matrix).iter_indexed() {
+            let value: i128 = value
+                .try_into()
+                .map_err(|_| anyhow!("Value at ({}, {}) doesn't fit in an i128", row, col))?;
+            sums[(row + 1, col + 1)] = value
+                .checked_add(sums[(row, col + 1)])
+                .and_then(|sum| sum.checked_add(sums[(row + 1, col)]))
+                .and_then(|sum| sum.checked_sub(sums[(row, col)]))
+                .ok_or_else(|| anyhow!("Overflow in prefix sum at ({}, {})", row, col))?;
+        }
+        Ok(Self { sums })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.sums.rows() - 1
+    }
+
+    pub fn cols(&self) -> usize {
+        self.sums.cols() - 1
+    }
+
+    /// Sum of the cells in `rows` x `cols`, in `O(1)`. Panics if out of bounds.
+    ///
+    /// Wrapping arithmetic keeps the result exact whenever the sum itself fits in an `i128`.
+    pub fn sum(&self, rows: Range<usize>, cols: Range<usize>) -> i128 {
+        if rows.is_empty() || cols.is_empty() {
+            return 0;
+        }
+        self.sums[(rows.end, cols.end)]
+            .wrapping_sub(self.sums[(rows.start, cols.end)])
+            .wrapping_sub(self.sums[(rows.end, cols.start)])
+            .wrapping_add(self.sums[(rows.start, cols.start)])
+    }
+
+    pub fn total(&self) -> i128 {
+        self.sum(0..self.rows(), 0..self.cols())
+    }
+
+    /// The `size`x`size` window with the largest sum, preferring the first in
+    /// row-major order on ties. `None` if no such window fits.
+    pub fn best_window(&self, size: usize) -> Option<Window> {
+        if size == 0 || size > self.rows() || size > self.cols() {
+            return None;
+        }
+        let mut best: Option<Window> = None;
+        for row in 0..=self.rows() - size {
+            for col in 0..=self.cols() - size {
+                let sum = self.sum(row..row + size, col..col + size);
+                if best.is_none_or(|best| sum > best.sum) {
+                    best = Some(Window {
+                        row,
+                        col,
+                        size,
+                        sum,
+                    });
+                }
+            }
+        }
+        best
+    }
+
+    /// `best_window` for every size that fits, smallest first.
+    pub fn best_windows(&self) -> impl Iterator<Item = Window> + '_ {
+        (1..=self.rows().min(self.cols())).filter_map(|size| self.best_window(size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sums() {
+        let m = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let sums = PrefixSums::new(&m).unwrap();
+        assert_eq!((sums.rows(), sums.cols()), (2, 3));
+        assert_eq!(sums.total(), 21);
+        assert_eq!(sums.sum(1..2, 1..3), 11);
+        assert_eq!(sums.sum(0..2, 2..3), 9);
+        assert_eq!(sums.sum(1..1, 0..3), 0);
+        assert_eq!(
+            PrefixSums::new(&(&m).transpose()).unwrap().sum(1..3, 0..1),
+            5
+        );
+        assert_eq!(PrefixSums::new(&Vec::<Vec<u8>>::new()).unwrap().total(), 0);
+    }
+
+    #[test]
+    fn test_wide_types() {
+        let sums = PrefixSums::new(&vec![vec![usize::MAX, 1], vec![2, 3]]).unwrap();
+        assert_eq!(sums.total(), usize::MAX as i128 + 6);
+        let big = vec![vec![u64::MAX; 2]; 2];
+        assert_eq!(PrefixSums::new(&big).unwrap().total(), 4 * u64::MAX as i128);
+        assert_eq!(PrefixSums::new(&vec![vec![-3isize, 4]]).unwrap().total(), 1);
+        assert!(PrefixSums::new(&vec![vec![u128::MAX]]).is_err());
+        assert!(PrefixSums::new(&vec![vec![i128::MAX, 1]]).is_err());
+    }
+
+    #[test]
+    fn test_best_windows() {
+        // Fuel cell power levels for grid serial number 18.
+        let serial: i32 = 18;
+        let grid = (1..=300)
+            .map(|y| {
+                (1..=300)
+                    .map(|x| {
+                        let rack = x + 10;
+                        (rack * y + serial) * rack / 100 % 10 - 5
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let sums = PrefixSums::new(&grid).unwrap();
+        assert_eq!(
+            sums.best_window(3),
+            Some(Window {
+                row: 44,
+                col: 32,
+                size: 3,
+                sum: 29,
+            })
+        );
+        let best = sums.best_windows().max_by_key(|window| window.sum).unwrap();
+        assert_eq!((best.col + 1, best.row + 1, best.size), (90, 269, 16));
+        assert_eq!(best.sum, 113);
+        assert_eq!(sums.best_window(301), None);
+        assert_eq!(sums.best_window(0), None);
+    }
+}
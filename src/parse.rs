@@ -1,5 +1,25 @@
+//! Input normalisation shared by all combinators: `\r\n` is treated as `\n`,
+//! trailing blank lines are ignored, sections are separated by one or more
+//! blank or whitespace-only lines and the final newline is optional.
+//!
+//! The one exception is zero-copy `ParseRef` parsing, which can only hand a
+//! multi-line section to its inner parser as a slice of the input, so a leaf
+//! like `Str` sees any `\r\n` inside that section as is.
+
+use crate::tile_map::TileMap;
 use anyhow::{anyhow, Result};
-use std::{fmt::Display, marker::PhantomData, str::FromStr};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    hash::Hash,
+    marker::PhantomData,
+    ops::{Range, RangeInclusive},
+    str::FromStr,
+};
+
+#[cfg(feature = "derive")]
+pub use aoc_helpers_derive::Parse;
 
 pub trait Parse {
     type Parsed;
@@ -20,6 +40,37 @@ where
     }
 }
 
+/// Lifetime-aware companion of `Parse` whose output may borrow from the raw input.
+///
+/// Every combinator in this module implements both traits, so `VecFromLines<Str>`
+/// yields `Vec<&str>` while `VecFromLines<usize>` works the same as with `Parse`.
+pub trait ParseRef<'a> {
+    type Parsed;
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed>;
+}
+
+impl<'a, T: FromStr> ParseRef<'a> for T
+where
+    T::Err: Display,
+{
+    type Parsed = T;
+
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        T::parse(raw_input)
+    }
+}
+
+/// Trimmed `&str` slice borrowed from the input.
+pub struct Str;
+
+impl<'a> ParseRef<'a> for Str {
+    type Parsed = &'a str;
+
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        Ok(raw_input.trim())
+    }
+}
+
 pub struct TrimAndParse<T> {
     _phantom: PhantomData<T>,
 }
@@ -38,6 +89,98 @@ where
     }
 }
 
+impl<'a, T: FromStr> ParseRef<'a> for TrimAndParse<T>
+where
+    T::Err: Display,
+{
+    type Parsed = T;
+
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        Self::parse(raw_input)
+    }
+}
+
+/// Adapts a hand-written `Parse` implementation to `ParseRef`, e.g.
+/// `VecFromLines<Owned<Custom>>`, where the output can't borrow from the input.
+pub struct Owned<P> {
+    _phantom: PhantomData<P>,
+}
+
+impl<P: Parse> Parse for Owned<P> {
+    type Parsed = P::Parsed;
+
+    fn parse(raw_input: &str) -> Result<Self::Parsed> {
+        P::parse(raw_input)
+    }
+}
+
+impl<'a, P: Parse> ParseRef<'a> for Owned<P> {
+    type Parsed = P::Parsed;
+
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        P::parse(raw_input)
+    }
+}
+
+/// Lines of the input with any trailing `\r` removed and trailing blank lines dropped,
+/// so CRLF input and a present or missing final newline all parse the same.
+fn lines(raw_input: &str) -> Vec<&str> {
+    let mut lines = raw_input
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect::<Vec<_>>();
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Byte ranges of the sections of the input, i.e. runs of lines separated by one
+/// or more blank (possibly whitespace-only) lines.
+fn section_ranges(raw_input: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut current: Option<Range<usize>> = None;
+    let mut offset = 0;
+    for line in raw_input.split('\n') {
+        let line_start = offset;
+        offset += line.len() + 1;
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.trim().is_empty() {
+            ranges.extend(current.take());
+        } else {
+            let start = current.map_or(line_start, |range| range.start);
+            current = Some(start..line_start + line.len());
+        }
+    }
+    ranges.extend(current);
+    ranges
+}
+
+fn sections(raw_input: &str) -> impl Iterator<Item = &str> {
+    section_ranges(raw_input)
+        .into_iter()
+        .map(move |range| &raw_input[range])
+}
+
+/// A section as owned parsers see it, with `\r\n` turned into `\n`.
+fn without_cr(section: &str) -> Cow<'_, str> {
+    if section.contains('\r') {
+        Cow::Owned(section.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(section)
+    }
+}
+
+fn parse_all<'a, P>(
+    parts: impl Iterator<Item = &'a str>,
+    parse: impl Fn(&'a str) -> Result<P>,
+) -> Result<Vec<P>> {
+    parts
+        .map(parse)
+        .collect::<Result<Vec<P>, _>>()
+        .map_err(|e| anyhow!("Parse failed: {}", e))
+}
+
 pub struct VecFromLines<T> {
     _phantom: PhantomData<T>,
 }
@@ -45,11 +188,14 @@ pub struct VecFromLines<T> {
 impl<T: Parse> Parse for VecFromLines<T> {
     type Parsed = Vec<T::Parsed>;
     fn parse(raw_input: &str) -> Result<Self::Parsed> {
-        raw_input
-            .lines()
-            .map(|line| T::parse(line))
-            .collect::<Result<Vec<T::Parsed>, _>>()
-            .map_err(|e| anyhow!("Parse failed: {}", e))
+        parse_all(lines(raw_input).into_iter(), T::parse)
+    }
+}
+
+impl<'a, T: ParseRef<'a>> ParseRef<'a> for VecFromLines<T> {
+    type Parsed = Vec<T::Parsed>;
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        parse_all(lines(raw_input).into_iter(), T::parse_ref)
     }
 }
 
@@ -60,30 +206,41 @@ pub struct VecFromMultiLines<T> {
 impl<T: Parse> Parse for VecFromMultiLines<T> {
     type Parsed = Vec<T::Parsed>;
     fn parse(raw_input: &str) -> Result<Self::Parsed> {
-        raw_input
-            .split("\n\n")
-            .map(|multiple_lines| T::parse(multiple_lines.trim()))
-            .collect::<Result<Vec<T::Parsed>, _>>()
-            .map_err(|e| anyhow!("Parse failed: {}", e))
+        parse_all(sections(raw_input), |section| {
+            T::parse(&without_cr(section))
+        })
     }
 }
 
+impl<'a, T: ParseRef<'a>> ParseRef<'a> for VecFromMultiLines<T> {
+    type Parsed = Vec<T::Parsed>;
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        parse_all(sections(raw_input), T::parse_ref)
+    }
+}
+
+fn comma_separated(raw_input: &str) -> impl Iterator<Item = &str> {
+    raw_input
+        .split(&[',', ' '])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
 pub struct VecFromCommaSeparated<T> {
     _phantom: PhantomData<T>,
 }
 
-impl<T: FromStr> Parse for VecFromCommaSeparated<T>
-where
-    T::Err: Display,
-{
-    type Parsed = Vec<T>;
+impl<T: Parse> Parse for VecFromCommaSeparated<T> {
+    type Parsed = Vec<T::Parsed>;
     fn parse(raw_input: &str) -> Result<Self::Parsed> {
-        raw_input
-            .split(&[',', ' '])
-            .filter(|s| !s.is_empty())
-            .map(|part| part.trim().parse::<T>())
-            .collect::<Result<Vec<T>, _>>()
-            .map_err(|e| anyhow!("Parse failed: {}", e))
+        parse_all(comma_separated(raw_input), T::parse)
+    }
+}
+
+impl<'a, T: ParseRef<'a>> ParseRef<'a> for VecFromCommaSeparated<T> {
+    type Parsed = Vec<T::Parsed>;
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        parse_all(comma_separated(raw_input), T::parse_ref)
     }
 }
 
@@ -94,12 +251,14 @@ pub struct VecFromWhitespaceSeparated<T> {
 impl<T: Parse> Parse for VecFromWhitespaceSeparated<T> {
     type Parsed = Vec<T::Parsed>;
     fn parse(raw_input: &str) -> Result<Self::Parsed> {
-        raw_input
-            .split_ascii_whitespace()
-            .filter(|s| !s.is_empty())
-            .map(|part| T::parse(part.trim()))
-            .collect::<Result<Vec<T::Parsed>, _>>()
-            .map_err(|e| anyhow!("Parse failed: {}", e))
+        parse_all(raw_input.split_ascii_whitespace(), T::parse)
+    }
+}
+
+impl<'a, T: ParseRef<'a>> ParseRef<'a> for VecFromWhitespaceSeparated<T> {
+    type Parsed = Vec<T::Parsed>;
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        parse_all(raw_input.split_ascii_whitespace(), T::parse_ref)
     }
 }
 
@@ -113,27 +272,827 @@ where
 {
     type Parsed = Vec<Vec<T>>;
     fn parse(raw_input: &str) -> Result<Self::Parsed> {
-        raw_input
-            .lines()
+        lines(raw_input)
+            .into_iter()
             .map(|line| line.chars().map(T::try_from).collect())
             .collect::<Result<Vec<Vec<T>>, _>>()
             .map_err(|e| anyhow!("Parse failed: {}", e))
     }
 }
 
+impl<'a, T: TryFrom<char>> ParseRef<'a> for RowsOfChars<T>
+where
+    T::Error: Display,
+{
+    type Parsed = Vec<Vec<T>>;
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        Self::parse(raw_input)
+    }
+}
+
+/// Special cell (like `S`, `E` or `@`) to locate while parsing a grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Marker {
+    pub symbol: char,
+    /// Char parsed in place of the marker cell.
+    pub replace_with: char,
+    pub required: bool,
+}
+
+impl Marker {
+    pub const fn required(symbol: char, replace_with: char) -> Self {
+        Self {
+            symbol,
+            replace_with,
+            required: true,
+        }
+    }
+
+    pub const fn optional(symbol: char, replace_with: char) -> Self {
+        Self {
+            symbol,
+            replace_with,
+            required: false,
+        }
+    }
+}
+
+pub trait MarkerSpec {
+    const MARKERS: &'static [Marker];
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MarkedGrid<T> {
+    pub grid: Vec<Vec<T>>,
+    /// `(row, col)` of every marker found, keyed by its symbol.
+    pub markers: HashMap<char, (usize, usize)>,
+}
+
+impl<T> MarkedGrid<T> {
+    pub fn marker(&self, symbol: char) -> Option<(usize, usize)> {
+        self.markers.get(&symbol).copied()
+    }
+
+    pub fn tile_map(&self) -> TileMap<T>
+    where
+        T: Copy,
+    {
+        TileMap::from(&self.grid)
+    }
+}
+
+/// Parses a grid like `RowsOfChars` while recording the position of each marker
+/// and substituting it with its replacement before conversion.
+pub fn parse_grid_with_markers<T: TryFrom<char>>(
+    raw_input: &str,
+    markers: &[Marker],
+) -> Result<MarkedGrid<T>>
+where
+    T::Error: Display,
+{
+    let mut found = HashMap::new();
+    let mut grid = Vec::new();
+    for (row, line) in lines(raw_input).into_iter().enumerate() {
+        let mut tiles = Vec::with_capacity(line.len());
+        for (col, c) in line.chars().enumerate() {
+            let c = match markers.iter().find(|marker| marker.symbol == c) {
+                Some(marker) => {
+                    if let Some((prev_row, prev_col)) = found.insert(marker.symbol, (row, col)) {
+                        return Err(anyhow!(
+                            "Duplicate marker {:?} at ({}, {}) and ({}, {})",
+                            marker.symbol,
+                            prev_row,
+                            prev_col,
+                            row,
+                            col
+                        ));
+                    }
+                    marker.replace_with
+                }
+                None => c,
+            };
+            tiles.push(T::try_from(c).map_err(|e| anyhow!("Parse failed: {}", e))?);
+        }
+        grid.push(tiles);
+    }
+    if let Some(missing) = markers
+        .iter()
+        .find(|marker| marker.required && !found.contains_key(&marker.symbol))
+    {
+        return Err(anyhow!("Missing marker {:?}", missing.symbol));
+    }
+    Ok(MarkedGrid {
+        grid,
+        markers: found,
+    })
+}
+
+pub struct GridWithMarkers<T, M> {
+    _phantom: PhantomData<(T, M)>,
+}
+
+impl<T: TryFrom<char>, M: MarkerSpec> Parse for GridWithMarkers<T, M>
+where
+    T::Error: Display,
+{
+    type Parsed = MarkedGrid<T>;
+    fn parse(raw_input: &str) -> Result<Self::Parsed> {
+        parse_grid_with_markers(raw_input, M::MARKERS)
+    }
+}
+
+impl<'a, T: TryFrom<char>, M: MarkerSpec> ParseRef<'a> for GridWithMarkers<T, M>
+where
+    T::Error: Display,
+{
+    type Parsed = MarkedGrid<T>;
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        parse_grid_with_markers(raw_input, M::MARKERS)
+    }
+}
+
 pub struct TwoSections<A, B> {
     _phantom: PhantomData<(A, B)>,
 }
 
+/// Splits off the first section; the second part is everything from the next section on.
+fn split_sections(raw_input: &str) -> Result<(&str, &str)> {
+    match section_ranges(raw_input).as_slice() {
+        [first, second, rest @ ..] => {
+            let end = rest.last().unwrap_or(second).end;
+            Ok((&raw_input[first.clone()], &raw_input[second.start..end]))
+        }
+        _ => Err(anyhow!("Failed to find 2 sections")),
+    }
+}
+
 impl<A: Parse, B: Parse> Parse for TwoSections<A, B> {
     type Parsed = (A::Parsed, B::Parsed);
     fn parse(raw_input: &str) -> Result<Self::Parsed> {
-        if let Some((first, second)) = raw_input.split_once("\n\n") {
-            Ok((A::parse(first)?, B::parse(second)?))
-        } else {
-            Err(anyhow!("Failed to find 2 sections"))
+        let (first, second) = split_sections(raw_input)?;
+        Ok((
+            A::parse(&without_cr(first))?,
+            B::parse(&without_cr(second))?,
+        ))
+    }
+}
+
+impl<'a, A: ParseRef<'a>, B: ParseRef<'a>> ParseRef<'a> for TwoSections<A, B> {
+    type Parsed = (A::Parsed, B::Parsed);
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        let (first, second) = split_sections(raw_input)?;
+        Ok((A::parse_ref(first)?, B::parse_ref(second)?))
+    }
+}
+
+/// Separator used by the configurable combinators below.
+pub trait Separator {
+    const SEPARATOR: &'static str;
+}
+
+macro_rules! separators {
+    ($($(#[$meta:meta])* $name:ident => $sep:literal),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            pub struct $name;
+
+            impl Separator for $name {
+                const SEPARATOR: &'static str = $sep;
+            }
+        )*
+    };
+}
+
+separators! {
+    /// `": "`-style separator (surrounding whitespace is trimmed).
+    Colon => ":",
+    /// `" -> "`-style separator (surrounding whitespace is trimmed).
+    Arrow => "->",
+    Equals => "=",
+    Comma => ",",
+    Semicolon => ";",
+    Pipe => "|",
+    /// Any run of spaces.
+    Space => " ",
+    Dash => "-",
+    DotDot => "..",
+}
+
+fn separated<S: Separator>(raw_input: &str) -> impl Iterator<Item = &str> {
+    raw_input
+        .split(S::SEPARATOR)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// Splits on `S` and parses every non-empty, trimmed part with `T`.
+pub struct VecFromSeparated<T, S> {
+    _phantom: PhantomData<(T, S)>,
+}
+
+impl<T: Parse, S: Separator> Parse for VecFromSeparated<T, S> {
+    type Parsed = Vec<T::Parsed>;
+    fn parse(raw_input: &str) -> Result<Self::Parsed> {
+        parse_all(separated::<S>(raw_input), T::parse)
+    }
+}
+
+impl<'a, T: ParseRef<'a>, S: Separator> ParseRef<'a> for VecFromSeparated<T, S> {
+    type Parsed = Vec<T::Parsed>;
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        parse_all(separated::<S>(raw_input), T::parse_ref)
+    }
+}
+
+fn split_key_value<S: Separator>(raw_input: &str) -> Result<(&str, &str)> {
+    raw_input
+        .split_once(S::SEPARATOR)
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .ok_or_else(|| anyhow!("Failed to find {:?} in {:?}", S::SEPARATOR, raw_input))
+}
+
+/// Splits once on `S` and parses the trimmed halves with `K` and `V`.
+pub struct KeyValue<K, V, S = Colon> {
+    _phantom: PhantomData<(K, V, S)>,
+}
+
+impl<K: Parse, V: Parse, S: Separator> Parse for KeyValue<K, V, S> {
+    type Parsed = (K::Parsed, V::Parsed);
+    fn parse(raw_input: &str) -> Result<Self::Parsed> {
+        let (key, value) = split_key_value::<S>(raw_input)?;
+        Ok((K::parse(key)?, V::parse(value)?))
+    }
+}
+
+impl<'a, K: ParseRef<'a>, V: ParseRef<'a>, S: Separator> ParseRef<'a> for KeyValue<K, V, S> {
+    type Parsed = (K::Parsed, V::Parsed);
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        let (key, value) = split_key_value::<S>(raw_input)?;
+        Ok((K::parse_ref(key)?, V::parse_ref(value)?))
+    }
+}
+
+trait InsertNew<K, V>: Default {
+    fn insert_new(&mut self, key: K, value: V) -> bool;
+}
+
+impl<K: Eq + Hash, V> InsertNew<K, V> for HashMap<K, V> {
+    fn insert_new(&mut self, key: K, value: V) -> bool {
+        self.insert(key, value).is_none()
+    }
+}
+
+impl<K: Ord, V> InsertNew<K, V> for BTreeMap<K, V> {
+    fn insert_new(&mut self, key: K, value: V) -> bool {
+        self.insert(key, value).is_none()
+    }
+}
+
+fn parse_map_lines<'a, K, V, M: InsertNew<K, V>>(
+    raw_input: &'a str,
+    parse_entry: impl Fn(&'a str) -> Result<(K, V)>,
+) -> Result<M> {
+    let mut map = M::default();
+    for (idx, line) in lines(raw_input).into_iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (key, value) =
+            parse_entry(line).map_err(|e| anyhow!("Parse failed on line {}: {}", idx + 1, e))?;
+        if !map.insert_new(key, value) {
+            return Err(anyhow!("Duplicate key on line {}: {:?}", idx + 1, line));
+        }
+    }
+    Ok(map)
+}
+
+/// Parses every non-empty line with `KeyValue<K, V, S>` into a `HashMap`.
+/// Duplicate keys are reported as errors.
+pub struct HashMapFromLines<K, V, S = Colon> {
+    _phantom: PhantomData<(K, V, S)>,
+}
+
+impl<K: Parse, V: Parse, S: Separator> Parse for HashMapFromLines<K, V, S>
+where
+    K::Parsed: Eq + Hash,
+{
+    type Parsed = HashMap<K::Parsed, V::Parsed>;
+    fn parse(raw_input: &str) -> Result<Self::Parsed> {
+        parse_map_lines(raw_input, KeyValue::<K, V, S>::parse)
+    }
+}
+
+impl<'a, K: ParseRef<'a>, V: ParseRef<'a>, S: Separator> ParseRef<'a> for HashMapFromLines<K, V, S>
+where
+    K::Parsed: Eq + Hash,
+{
+    type Parsed = HashMap<K::Parsed, V::Parsed>;
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        parse_map_lines(raw_input, KeyValue::<K, V, S>::parse_ref)
+    }
+}
+
+/// Like `HashMapFromLines` but produces a `BTreeMap`.
+pub struct BTreeMapFromLines<K, V, S = Colon> {
+    _phantom: PhantomData<(K, V, S)>,
+}
+
+impl<K: Parse, V: Parse, S: Separator> Parse for BTreeMapFromLines<K, V, S>
+where
+    K::Parsed: Ord,
+{
+    type Parsed = BTreeMap<K::Parsed, V::Parsed>;
+    fn parse(raw_input: &str) -> Result<Self::Parsed> {
+        parse_map_lines(raw_input, KeyValue::<K, V, S>::parse)
+    }
+}
+
+impl<'a, K: ParseRef<'a>, V: ParseRef<'a>, S: Separator> ParseRef<'a> for BTreeMapFromLines<K, V, S>
+where
+    K::Parsed: Ord,
+{
+    type Parsed = BTreeMap<K::Parsed, V::Parsed>;
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        parse_map_lines(raw_input, KeyValue::<K, V, S>::parse_ref)
+    }
+}
+
+/// Adjacency map from lines like `abc: def ghi` or `AA -> BB, CC`.
+/// `KS` separates the key from its list, `VS` separates the list items.
+pub type AdjacencyHashMap<K, V, KS = Colon, VS = Space> =
+    HashMapFromLines<K, VecFromSeparated<V, VS>, KS>;
+
+/// Like `AdjacencyHashMap` but produces a `BTreeMap`.
+pub type AdjacencyBTreeMap<K, V, KS = Colon, VS = Space> =
+    BTreeMapFromLines<K, VecFromSeparated<V, VS>, KS>;
+
+/// Two values separated by `S`, e.g. `2-4,6-8` as `Pair<InclusiveRange<usize>, InclusiveRange<usize>>`.
+pub type Pair<A, B, S = Comma> = KeyValue<A, B, S>;
+
+/// Splits a range like `3-7`, `-5..-1` or `x=-5..5` into its trimmed bounds.
+/// A leading `name=` is skipped and the separator is never taken from the
+/// first char so that negative start bounds work with `Dash`.
+fn split_range<S: Separator>(raw_input: &str) -> Result<(&str, &str)> {
+    let mut range = raw_input.trim();
+    if let Some((name, rest)) = range.split_once('=') {
+        if name.trim().chars().all(char::is_alphanumeric) {
+            range = rest.trim();
         }
     }
+    let skip = range.chars().next().map_or(0, char::len_utf8);
+    range[skip..]
+        .find(S::SEPARATOR)
+        .map(|idx| {
+            (
+                range[..skip + idx].trim(),
+                range[skip + idx + S::SEPARATOR.len()..].trim(),
+            )
+        })
+        .ok_or_else(|| anyhow!("Failed to find {:?} in range {:?}", S::SEPARATOR, raw_input))
+}
+
+/// Range with both bounds included, like `3-7` (default) or `x=-5..5` with `DotDot`.
+pub struct InclusiveRange<T, S = Dash> {
+    _phantom: PhantomData<(T, S)>,
+}
+
+impl<T: Parse, S: Separator> Parse for InclusiveRange<T, S> {
+    type Parsed = RangeInclusive<T::Parsed>;
+    fn parse(raw_input: &str) -> Result<Self::Parsed> {
+        let (start, end) = split_range::<S>(raw_input)?;
+        Ok(T::parse(start)?..=T::parse(end)?)
+    }
+}
+
+impl<'a, T: ParseRef<'a>, S: Separator> ParseRef<'a> for InclusiveRange<T, S> {
+    type Parsed = RangeInclusive<T::Parsed>;
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        let (start, end) = split_range::<S>(raw_input)?;
+        Ok(T::parse_ref(start)?..=T::parse_ref(end)?)
+    }
+}
+
+/// Range with the end bound excluded, like `10..20` (default).
+pub struct HalfOpenRange<T, S = DotDot> {
+    _phantom: PhantomData<(T, S)>,
+}
+
+impl<T: Parse, S: Separator> Parse for HalfOpenRange<T, S> {
+    type Parsed = Range<T::Parsed>;
+    fn parse(raw_input: &str) -> Result<Self::Parsed> {
+        let (start, end) = split_range::<S>(raw_input)?;
+        Ok(T::parse(start)?..T::parse(end)?)
+    }
+}
+
+impl<'a, T: ParseRef<'a>, S: Separator> ParseRef<'a> for HalfOpenRange<T, S> {
+    type Parsed = Range<T::Parsed>;
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        let (start, end) = split_range::<S>(raw_input)?;
+        Ok(T::parse_ref(start)?..T::parse_ref(end)?)
+    }
+}
+
+/// Result of trying `A` and then `B`; also usable directly as a combinator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Either<A, B> {
+    Left(A),
+    Right(B),
+}
+
+fn no_alternative_matched(raw_input: &str, errors: Vec<anyhow::Error>) -> anyhow::Error {
+    let reasons = errors
+        .iter()
+        .enumerate()
+        .map(|(idx, e)| format!("#{}: {}", idx + 1, e))
+        .collect::<Vec<_>>()
+        .join("; ");
+    anyhow!("No alternative matched {:?} ({})", raw_input, reasons)
+}
+
+impl<A: Parse, B: Parse> Parse for Either<A, B> {
+    type Parsed = Either<A::Parsed, B::Parsed>;
+    fn parse(raw_input: &str) -> Result<Self::Parsed> {
+        A::parse(raw_input).map(Either::Left).or_else(|left| {
+            B::parse(raw_input)
+                .map(Either::Right)
+                .map_err(|right| no_alternative_matched(raw_input, vec![left, right]))
+        })
+    }
+}
+
+impl<'a, A: ParseRef<'a>, B: ParseRef<'a>> ParseRef<'a> for Either<A, B> {
+    type Parsed = Either<A::Parsed, B::Parsed>;
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        A::parse_ref(raw_input).map(Either::Left).or_else(|left| {
+            B::parse_ref(raw_input)
+                .map(Either::Right)
+                .map_err(|right| no_alternative_matched(raw_input, vec![left, right]))
+        })
+    }
+}
+
+/// Tries every parser of the tuple in turn; all of them must produce the same type.
+pub struct OneOf<T> {
+    _phantom: PhantomData<T>,
+}
+
+macro_rules! one_of {
+    ($($name:ident),+) => {
+        impl<P, $($name: Parse<Parsed = P>),+> Parse for OneOf<($($name,)+)> {
+            type Parsed = P;
+            fn parse(raw_input: &str) -> Result<Self::Parsed> {
+                let mut errors = Vec::new();
+                $(
+                    match $name::parse(raw_input) {
+                        Ok(parsed) => return Ok(parsed),
+                        Err(e) => errors.push(e),
+                    }
+                )+
+                Err(no_alternative_matched(raw_input, errors))
+            }
+        }
+
+        impl<'a, P, $($name: ParseRef<'a, Parsed = P>),+> ParseRef<'a> for OneOf<($($name,)+)> {
+            type Parsed = P;
+            fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+                let mut errors = Vec::new();
+                $(
+                    match $name::parse_ref(raw_input) {
+                        Ok(parsed) => return Ok(parsed),
+                        Err(e) => errors.push(e),
+                    }
+                )+
+                Err(no_alternative_matched(raw_input, errors))
+            }
+        }
+    };
+}
+
+one_of!(A, B);
+one_of!(A, B, C);
+one_of!(A, B, C, D);
+one_of!(A, B, C, D, E);
+one_of!(A, B, C, D, E, F);
+one_of!(A, B, C, D, E, F, G);
+one_of!(A, B, C, D, E, F, G, H);
+
+/// Keyword and the function building a value from the (trimmed) rest of the line.
+pub type KeywordParser<'k, 'a, T> = (&'k str, &'k dyn Fn(&'a str) -> Result<T>);
+
+/// Matches the first word of `raw_input` against `keywords` and hands the rest
+/// of the input to the corresponding function, e.g. `("addx", &|arg| Ok(Instr::Addx(arg.parse()?)))`.
+pub fn parse_keyword<'a, T>(
+    raw_input: &'a str,
+    keywords: &[KeywordParser<'_, 'a, T>],
+) -> Result<T> {
+    let raw_input = raw_input.trim();
+    let (word, rest) = raw_input
+        .split_once(char::is_whitespace)
+        .unwrap_or((raw_input, ""));
+    let (keyword, parse) = keywords
+        .iter()
+        .find(|(keyword, _)| *keyword == word)
+        .ok_or_else(|| {
+            anyhow!(
+                "Unknown keyword {:?}, expected one of {:?}",
+                word,
+                keywords
+                    .iter()
+                    .map(|(keyword, _)| keyword)
+                    .collect::<Vec<_>>()
+            )
+        })?;
+    parse(rest.trim()).map_err(|e| anyhow!("Parse failed for {:?}: {}", keyword, e))
+}
+
+/// Recursive bracketed list like `[1,[2,[3,4]],[]]`.
+///
+/// Equality is structural; see `packet_cmp` for the distress-signal ordering.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Nested<T> {
+    Leaf(T),
+    List(Vec<Nested<T>>),
+}
+
+impl<T> Nested<T> {
+    pub fn is_leaf(&self) -> bool {
+        matches!(self, Self::Leaf(_))
+    }
+
+    /// Compares leaves directly, lists element-wise, and a leaf against a list as
+    /// if the leaf were a single-element list, so `[[2]]` and `2` compare equal.
+    pub fn packet_cmp(&self, other: &Self) -> std::cmp::Ordering
+    where
+        T: Ord,
+    {
+        fn lists<T: Ord>(a: &[Nested<T>], b: &[Nested<T>]) -> std::cmp::Ordering {
+            a.iter()
+                .zip(b)
+                .map(|(a, b)| a.packet_cmp(b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or_else(|| a.len().cmp(&b.len()))
+        }
+
+        match (self, other) {
+            (Self::Leaf(a), Self::Leaf(b)) => a.cmp(b),
+            (Self::List(a), Self::List(b)) => lists(a, b),
+            (Self::Leaf(_), Self::List(b)) => lists(std::slice::from_ref(self), b),
+            (Self::List(a), Self::Leaf(_)) => lists(a, std::slice::from_ref(other)),
+        }
+    }
+}
+
+impl<T: Display> Display for Nested<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Leaf(leaf) => write!(f, "{}", leaf),
+            Self::List(items) => {
+                write!(f, "[")?;
+                for (idx, item) in items.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+fn parse_nested_prefix<'a, P>(
+    raw_input: &'a str,
+    parse_leaf: &impl Fn(&'a str) -> Result<P>,
+) -> Result<(Nested<P>, &'a str)> {
+    let raw_input = raw_input.trim_start();
+    if let Some(mut rest) = raw_input.strip_prefix('[') {
+        let mut items = Vec::new();
+        if let Some(rest) = rest.trim_start().strip_prefix(']') {
+            return Ok((Nested::List(items), rest));
+        }
+        loop {
+            let (item, after_item) = parse_nested_prefix(rest, parse_leaf)?;
+            items.push(item);
+            let after_item = after_item.trim_start();
+            if let Some(after_comma) = after_item.strip_prefix(',') {
+                rest = after_comma;
+            } else if let Some(after_list) = after_item.strip_prefix(']') {
+                return Ok((Nested::List(items), after_list));
+            } else {
+                return Err(anyhow!("Expected ',' or ']' before {:?}", after_item));
+            }
+        }
+    } else {
+        let end = raw_input.find([',', '[', ']']).unwrap_or(raw_input.len());
+        let leaf = raw_input[..end].trim();
+        if leaf.is_empty() {
+            return Err(anyhow!("Expected a value before {:?}", raw_input));
+        }
+        Ok((Nested::Leaf(parse_leaf(leaf)?), &raw_input[end..]))
+    }
+}
+
+fn parse_nested<'a, P>(
+    raw_input: &'a str,
+    parse_leaf: impl Fn(&'a str) -> Result<P>,
+) -> Result<Nested<P>> {
+    let (nested, rest) = parse_nested_prefix(raw_input, &parse_leaf)?;
+    if rest.trim().is_empty() {
+        Ok(nested)
+    } else {
+        Err(anyhow!("Unexpected trailing input {:?}", rest))
+    }
+}
+
+impl<T: Parse> Parse for Nested<T> {
+    type Parsed = Nested<T::Parsed>;
+    fn parse(raw_input: &str) -> Result<Self::Parsed> {
+        parse_nested(raw_input, T::parse)
+    }
+}
+
+impl<'a, T: ParseRef<'a>> ParseRef<'a> for Nested<T> {
+    type Parsed = Nested<T::Parsed>;
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        parse_nested(raw_input, T::parse_ref)
+    }
+}
+
+/// Describes how `Columns` finds the char span of every column.
+pub trait ColumnLayout {
+    /// Returns the char spans of the columns and how many lines (from the top) hold cells.
+    fn spans(lines: &[&str]) -> Result<(Vec<Range<usize>>, usize)>;
+}
+
+/// Columns are consecutive chunks of `WIDTH` chars.
+pub struct FixedWidth<const WIDTH: usize>;
+
+impl<const WIDTH: usize> ColumnLayout for FixedWidth<WIDTH> {
+    fn spans(lines: &[&str]) -> Result<(Vec<Range<usize>>, usize)> {
+        if WIDTH == 0 {
+            return Err(anyhow!("Column width must be positive"));
+        }
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let spans = (0..width)
+            .step_by(WIDTH)
+            .map(|start| start..start + WIDTH)
+            .collect();
+        Ok((spans, lines.len()))
+    }
+}
+
+/// The last line holds labels (like ` 1   2   3 `). Columns are split halfway
+/// between neighbouring labels, so cells may be wider than their label.
+pub struct FooterLabels;
+
+impl ColumnLayout for FooterLabels {
+    fn spans(lines: &[&str]) -> Result<(Vec<Range<usize>>, usize)> {
+        let (footer, cells) = lines
+            .split_last()
+            .ok_or_else(|| anyhow!("Failed to find footer labels"))?;
+        let mut labels = Vec::new();
+        let mut start = None;
+        for (idx, c) in footer.chars().chain(std::iter::once(' ')).enumerate() {
+            match (start, c.is_whitespace()) {
+                (None, false) => start = Some(idx),
+                (Some(label_start), true) => {
+                    labels.push(label_start..idx);
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        let mut boundaries = vec![0];
+        boundaries.extend(
+            labels
+                .windows(2)
+                .map(|pair| (pair[0].end + pair[1].start) / 2),
+        );
+        boundaries.push(usize::MAX);
+        let spans = boundaries
+            .windows(2)
+            .take(labels.len())
+            .map(|pair| pair[0]..pair[1])
+            .collect();
+        Ok((spans, cells.len()))
+    }
+}
+
+pub trait ColumnOrder {
+    const BOTTOM_UP: bool;
+}
+
+pub struct BottomUp;
+
+impl ColumnOrder for BottomUp {
+    const BOTTOM_UP: bool = true;
+}
+
+pub struct TopDown;
+
+impl ColumnOrder for TopDown {
+    const BOTTOM_UP: bool = false;
+}
+
+fn char_span<'a>(line: &'a str, span: &Range<usize>) -> &'a str {
+    let byte_offset = |idx: usize| {
+        line.char_indices()
+            .nth(idx)
+            .map(|(offset, _)| offset)
+            .unwrap_or(line.len())
+    };
+    &line[byte_offset(span.start)..byte_offset(span.end)]
+}
+
+/// Drops one pair of surrounding brackets, so crates drawn as `[A]` parse as `A`.
+fn unbracket(cell: &str) -> &str {
+    cell.strip_prefix('[')
+        .and_then(|inner| inner.strip_suffix(']'))
+        .map_or(cell, str::trim)
+}
+
+fn parse_columns<'a, L: ColumnLayout, O: ColumnOrder, P>(
+    raw_input: &'a str,
+    parse: impl Fn(&'a str) -> Result<P>,
+) -> Result<Vec<Vec<P>>> {
+    let lines = lines(raw_input);
+    let (spans, cell_lines) = L::spans(&lines)?;
+    let mut cell_lines = lines[..cell_lines].to_vec();
+    if O::BOTTOM_UP {
+        cell_lines.reverse();
+    }
+    spans
+        .iter()
+        .map(|span| {
+            parse_all(
+                cell_lines
+                    .iter()
+                    .map(|line| unbracket(char_span(line, span).trim()))
+                    .filter(|cell| !cell.is_empty()),
+                &parse,
+            )
+        })
+        .collect()
+}
+
+/// Splits a visually laid out block (like stacked crates) into columns and parses
+/// every non-blank, trimmed cell with `T`, in the order selected by `O`. A pair of
+/// brackets around a cell is removed first, so `[Z]` parses as a `char`.
+pub struct Columns<T, L = FooterLabels, O = BottomUp> {
+    _phantom: PhantomData<(T, L, O)>,
+}
+
+impl<T: Parse, L: ColumnLayout, O: ColumnOrder> Parse for Columns<T, L, O> {
+    type Parsed = Vec<Vec<T::Parsed>>;
+    fn parse(raw_input: &str) -> Result<Self::Parsed> {
+        parse_columns::<L, O, _>(raw_input, T::parse)
+    }
+}
+
+impl<'a, T: ParseRef<'a>, L: ColumnLayout, O: ColumnOrder> ParseRef<'a> for Columns<T, L, O> {
+    type Parsed = Vec<Vec<T::Parsed>>;
+    fn parse_ref(raw_input: &'a str) -> Result<Self::Parsed> {
+        parse_columns::<L, O, _>(raw_input, T::parse_ref)
+    }
+}
+
+/// Matches `raw_input` (trimmed) against a format made of `literals` with a
+/// placeholder between each consecutive pair, returning the trimmed placeholder
+/// values. Each value extends up to the first occurrence of the following
+/// literal, the last one up to the end of the input when the final literal is empty.
+///
+/// This backs `#[derive(Parse)]`, e.g. `"{name} can fly {speed} km/s"` becomes
+/// `&["", " can fly ", " km/s"]`.
+pub fn match_format<'a>(raw_input: &'a str, literals: &[&str]) -> Result<Vec<&'a str>> {
+    let (first, rest) = literals
+        .split_first()
+        .ok_or_else(|| anyhow!("Format needs at least one literal"))?;
+    let mut input = raw_input
+        .trim()
+        .strip_prefix(first)
+        .ok_or_else(|| anyhow!("Expected {:?} at the start of {:?}", first, raw_input))?;
+    let mut values = Vec::with_capacity(rest.len());
+    for literal in rest {
+        let end = if literal.is_empty() {
+            input.len()
+        } else {
+            input
+                .find(literal)
+                .ok_or_else(|| anyhow!("Expected {:?} in {:?}", literal, input))?
+        };
+        values.push(input[..end].trim());
+        input = &input[end + literal.len()..];
+    }
+    if input.is_empty() {
+        Ok(values)
+    } else {
+        Err(anyhow!("Unexpected trailing input {:?}", input))
+    }
 }
 
 #[cfg(test)]
@@ -191,6 +1150,7 @@ mod tests {
     #[test]
     fn test_rows_of_chars() {
         #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        #[allow(clippy::upper_case_acronyms)]
         enum ABC {
             A,
             B,
@@ -220,4 +1180,401 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_key_value() {
+        assert_eq!(
+            KeyValue::<String, usize>::parse("foo: 42").unwrap(),
+            ("foo".to_owned(), 42)
+        );
+        assert_eq!(
+            KeyValue::<usize, isize, Equals>::parse("1=-2").unwrap(),
+            (1, -2)
+        );
+        assert!(KeyValue::<String, usize>::parse("foo 42").is_err());
+    }
+
+    #[test]
+    fn test_vec_from_separated() {
+        assert_eq!(
+            VecFromSeparated::<usize, Pipe>::parse("1 | 2|3 ").unwrap(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            VecFromSeparated::<usize, Space>::parse(" 1  2 3").unwrap(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_adjacency_maps() {
+        let map = AdjacencyHashMap::<String, String>::parse("abc: def ghi\nxyz: abc\n").unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["abc"], vec!["def".to_owned(), "ghi".to_owned()]);
+        assert_eq!(map["xyz"], vec!["abc".to_owned()]);
+
+        let map =
+            AdjacencyBTreeMap::<String, String, Arrow, Comma>::parse("AA -> BB, CC\nBB -> AA")
+                .unwrap();
+        assert_eq!(
+            map.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("AA".to_owned(), vec!["BB".to_owned(), "CC".to_owned()]),
+                ("BB".to_owned(), vec!["AA".to_owned()]),
+            ]
+        );
+
+        assert!(AdjacencyHashMap::<String, String>::parse("a: b\na: c").is_err());
+    }
+
+    #[test]
+    fn test_grid_with_markers() {
+        struct StartEnd;
+        impl MarkerSpec for StartEnd {
+            const MARKERS: &'static [Marker] = &[
+                Marker::required('S', '.'),
+                Marker::required('E', '.'),
+                Marker::optional('@', '#'),
+            ];
+        }
+
+        let parsed = GridWithMarkers::<char, StartEnd>::parse("S.#\n.#E").unwrap();
+        assert_eq!(parsed.grid, vec![vec!['.', '.', '#'], vec!['.', '#', '.']]);
+        assert_eq!(parsed.marker('S'), Some((0, 0)));
+        assert_eq!(parsed.marker('E'), Some((1, 2)));
+        assert_eq!(parsed.marker('@'), None);
+        assert_eq!(parsed.tile_map().get(1, 2), Some('.'));
+
+        assert!(GridWithMarkers::<char, StartEnd>::parse("S.#\n.#.").is_err());
+        assert!(GridWithMarkers::<char, StartEnd>::parse("S.E\n.#E").is_err());
+    }
+
+    #[test]
+    fn test_ranges() {
+        assert_eq!(InclusiveRange::<usize>::parse("3-7").unwrap(), 3..=7);
+        assert_eq!(InclusiveRange::<isize>::parse("-5--1").unwrap(), -5..=-1);
+        assert_eq!(
+            InclusiveRange::<isize, DotDot>::parse("-5..-1").unwrap(),
+            -5..=-1
+        );
+        assert_eq!(
+            InclusiveRange::<isize, DotDot>::parse("x=-5..5").unwrap(),
+            -5..=5
+        );
+        assert_eq!(HalfOpenRange::<usize>::parse("10..20").unwrap(), 10..20);
+        assert!(HalfOpenRange::<usize>::parse("10-20").is_err());
+        assert_eq!(
+            InclusiveRange::<String>::parse("é-b").unwrap(),
+            "é".to_owned()..="b".to_owned()
+        );
+        assert!(InclusiveRange::<String>::parse("é").is_err());
+
+        assert_eq!(
+            VecFromCommaSeparated::<InclusiveRange<isize, DotDot>>::parse("x=-5..5, y=1..3")
+                .unwrap(),
+            vec![-5..=5, 1..=3]
+        );
+        assert_eq!(
+            Pair::<InclusiveRange<usize>, InclusiveRange<usize>>::parse("2-4,6-8").unwrap(),
+            (2..=4, 6..=8)
+        );
+    }
+
+    #[test]
+    fn test_parse_ref_borrows() {
+        let raw_input = String::from("abc def\nghi");
+        let words: Vec<Vec<&str>> =
+            VecFromLines::<VecFromWhitespaceSeparated<Str>>::parse_ref(&raw_input).unwrap();
+        assert_eq!(words, vec![vec!["abc", "def"], vec!["ghi"]]);
+        assert!(std::ptr::eq(words[0][0].as_ptr(), raw_input.as_ptr()));
+
+        assert_eq!(
+            VecFromLines::<usize>::parse_ref("1\n2\n3").unwrap(),
+            vec![1, 2, 3]
+        );
+
+        let map = AdjacencyHashMap::<Str, Str, Arrow, Comma>::parse_ref("AA -> BB, CC").unwrap();
+        assert_eq!(map["AA"], vec!["BB", "CC"]);
+    }
+
+    #[test]
+    fn test_owned() {
+        struct Doubled;
+        impl Parse for Doubled {
+            type Parsed = usize;
+            fn parse(raw_input: &str) -> Result<Self::Parsed> {
+                Ok(usize::parse(raw_input)? * 2)
+            }
+        }
+
+        assert_eq!(
+            VecFromLines::<Owned<Doubled>>::parse_ref("1\n2").unwrap(),
+            vec![2, 4]
+        );
+        assert_eq!(
+            Owned::<VecFromLines<Doubled>>::parse_ref("3").unwrap(),
+            vec![6]
+        );
+        let (words, doubled) =
+            TwoSections::<VecFromLines<Str>, VecFromLines<Owned<Doubled>>>::parse_ref("a\n\n5")
+                .unwrap();
+        assert_eq!((words, doubled), (vec!["a"], vec![10]));
+        assert!(Owned::<Doubled>::parse("x").is_err());
+    }
+
+    #[test]
+    fn test_either() {
+        assert_eq!(
+            VecFromWhitespaceSeparated::<Either<usize, String>>::parse("1 foo").unwrap(),
+            vec![Either::Left(1), Either::Right("foo".to_owned())]
+        );
+        let e = Either::<usize, InclusiveRange<usize>>::parse("foo").unwrap_err();
+        assert!(e.to_string().contains("#1"));
+        assert!(e.to_string().contains("#2"));
+    }
+
+    #[test]
+    fn test_one_of_and_keywords() {
+        use crate::interpret::{Execute, Jump};
+
+        #[derive(Debug, PartialEq, Eq)]
+        enum Instr {
+            Noop,
+            Addx(isize),
+        }
+
+        impl FromStr for Instr {
+            type Err = anyhow::Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                parse_keyword(
+                    s,
+                    &[
+                        ("noop", &|_| Ok(Self::Noop)),
+                        ("addx", &|arg| Ok(Self::Addx(arg.parse()?))),
+                    ],
+                )
+            }
+        }
+
+        impl Execute<isize> for Instr {
+            fn execute(&self, state: isize) -> (isize, Jump) {
+                match self {
+                    Self::Noop => (state, Jump::default()),
+                    Self::Addx(x) => (state + x, Jump::default()),
+                }
+            }
+        }
+
+        let program = VecFromLines::<Instr>::parse("noop\naddx 3\naddx -5").unwrap();
+        assert_eq!(program, vec![Instr::Noop, Instr::Addx(3), Instr::Addx(-5)]);
+        assert_eq!(program.execute(1), (-1, Jump::Stop));
+        assert!(Instr::parse("addy 3").is_err());
+        assert!(Instr::parse("addx y").is_err());
+
+        struct Noop;
+        impl Parse for Noop {
+            type Parsed = Instr;
+            fn parse(raw_input: &str) -> Result<Self::Parsed> {
+                match raw_input.trim() {
+                    "noop" => Ok(Instr::Noop),
+                    _ => Err(anyhow!("Not a noop")),
+                }
+            }
+        }
+
+        struct Addx;
+        impl Parse for Addx {
+            type Parsed = Instr;
+            fn parse(raw_input: &str) -> Result<Self::Parsed> {
+                let (_, x) = split_key_value::<Space>(raw_input)?;
+                Ok(Instr::Addx(isize::parse(x)?))
+            }
+        }
+
+        assert_eq!(
+            VecFromLines::<OneOf<(Noop, Addx)>>::parse("noop\naddx 3").unwrap(),
+            vec![Instr::Noop, Instr::Addx(3)]
+        );
+        let e = OneOf::<(Noop, Addx, Noop)>::parse("x").unwrap_err();
+        assert!(e.to_string().contains("#3"));
+    }
+
+    #[test]
+    fn test_nested() {
+        let packet = Nested::<usize>::parse("[1,[2,[3,4]],[]]").unwrap();
+        assert_eq!(packet.to_string(), "[1,[2,[3,4]],[]]");
+        assert_eq!(
+            Nested::<usize>::parse(" [ 1 , [ ] ] ").unwrap().to_string(),
+            "[1,[]]"
+        );
+        assert!(Nested::<usize>::parse("5").unwrap().is_leaf());
+        assert!(Nested::<usize>::parse("[1,2").is_err());
+        assert!(Nested::<usize>::parse("[1,,2]").is_err());
+        assert!(Nested::<usize>::parse("[1]2").is_err());
+        assert!(Nested::<usize>::parse("[a]").is_err());
+
+        let pairs = [
+            ("[1,1,3,1,1]", "[1,1,5,1,1]", true),
+            ("[[1],[2,3,4]]", "[[1],4]", true),
+            ("[9]", "[[8,7,6]]", false),
+            ("[[4,4],4,4]", "[[4,4],4,4,4]", true),
+            ("[7,7,7,7]", "[7,7,7]", false),
+            ("[]", "[3]", true),
+            ("[[[]]]", "[[]]", false),
+            (
+                "[1,[2,[3,[4,[5,6,7]]]],8,9]",
+                "[1,[2,[3,[4,[5,6,0]]]],8,9]",
+                false,
+            ),
+        ];
+        for (left, right, in_order) in pairs {
+            let left = Nested::<usize>::parse(left).unwrap();
+            let right = Nested::<usize>::parse(right).unwrap();
+            assert_eq!(
+                left.packet_cmp(&right).is_lt(),
+                in_order,
+                "{} vs {}",
+                left,
+                right
+            );
+        }
+        let (deep, leaf) = (
+            Nested::<usize>::parse("[[2]]").unwrap(),
+            Nested::<usize>::parse("2").unwrap(),
+        );
+        assert!(deep.packet_cmp(&leaf).is_eq());
+        assert_ne!(deep, leaf);
+        assert_eq!(
+            Nested::<usize>::parse("[[1,2],3]").unwrap(),
+            Nested::List(vec![
+                Nested::List(vec![Nested::Leaf(1), Nested::Leaf(2)]),
+                Nested::Leaf(3),
+            ])
+        );
+
+        let mut packets = ["[2]", "[[1]]", "[]", "3"]
+            .map(|packet| Nested::<usize>::parse(packet).unwrap())
+            .to_vec();
+        packets.sort_by(Nested::packet_cmp);
+        assert_eq!(
+            packets.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["[]", "[[1]]", "[2]", "3"]
+        );
+    }
+
+    #[test]
+    fn test_columns() {
+        let stacks = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n";
+        assert_eq!(
+            Columns::<char>::parse(stacks).unwrap(),
+            vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]
+        );
+        assert_eq!(
+            Columns::<char, FooterLabels, TopDown>::parse(stacks).unwrap(),
+            vec![vec!['N', 'Z'], vec!['D', 'C', 'M'], vec!['P']]
+        );
+        assert_eq!(
+            Columns::<Str>::parse_ref(stacks).unwrap(),
+            vec![vec!["Z", "N"], vec!["M", "C", "D"], vec!["P"]]
+        );
+        let (stacks, moves) = TwoSections::<Columns<char>, VecFromLines<String>>::parse(&format!(
+            "{}\nmove 1",
+            stacks
+        ))
+        .unwrap();
+        assert_eq!(stacks.len(), 3);
+        assert_eq!(moves, vec!["move 1".to_owned()]);
+
+        assert_eq!(
+            Columns::<usize>::parse("[10] [2]\n 1    2 ").unwrap(),
+            vec![vec![10], vec![2]]
+        );
+        assert_eq!(
+            Columns::<Str>::parse_ref("[ab  [c]\n 1    2 ").unwrap(),
+            vec![vec!["[ab"], vec!["c"]]
+        );
+
+        assert_eq!(
+            Columns::<usize, FixedWidth<4>, TopDown>::parse("123 328\n 45 64\n  6 9").unwrap(),
+            vec![vec![123, 45, 6], vec![328, 64, 9]]
+        );
+        assert_eq!(
+            Columns::<Str, FixedWidth<2>>::parse_ref("ab\ncd").unwrap(),
+            vec![vec!["cd", "ab"]]
+        );
+    }
+
+    #[test]
+    fn test_match_format() {
+        assert_eq!(
+            match_format("Comet can fly 14 km/s", &["", " can fly ", " km/s"]).unwrap(),
+            vec!["Comet", "14"]
+        );
+        assert_eq!(
+            match_format("move 1 from 2 to 3", &["move ", " from ", " to ", ""]).unwrap(),
+            vec!["1", "2", "3"]
+        );
+        assert_eq!(match_format("noop", &["noop"]).unwrap(), Vec::<&str>::new());
+        assert!(match_format("noop 1", &["noop"]).is_err());
+        assert!(match_format("Comet can fly 14", &["", " can fly ", " km/s"]).is_err());
+    }
+
+    fn assert_normalised<P: Parse>(input: &str)
+    where
+        P::Parsed: PartialEq + std::fmt::Debug,
+    {
+        let expected = P::parse(input).unwrap();
+        let crlf = input.replace('\n', "\r\n");
+        let variants = [
+            format!("{}\n", input),
+            format!("{}\n\n \n", input),
+            crlf.clone(),
+            format!("{}\r\n", crlf),
+            format!("{}\r\n\r\n", crlf),
+            input.replace("\n\n", "\n  \n"),
+            input.replace("\n\n", "\n\t\n\n"),
+            crlf.replace("\r\n\r\n", "\r\n \r\n"),
+        ];
+        for variant in variants {
+            assert_eq!(P::parse(&variant).unwrap(), expected, "{:?}", variant);
+        }
+    }
+
+    #[test]
+    fn test_normalisation_matrix() {
+        struct Start;
+        impl MarkerSpec for Start {
+            const MARKERS: &'static [Marker] = &[Marker::required('S', '.')];
+        }
+
+        assert_normalised::<VecFromLines<usize>>("1\n2\n3");
+        assert_normalised::<VecFromMultiLines<VecFromLines<usize>>>("1\n2\n\n3\n\n\n4");
+        assert_normalised::<VecFromCommaSeparated<usize>>("1, 2,3");
+        assert_normalised::<VecFromWhitespaceSeparated<usize>>("1 2\n3");
+        assert_normalised::<VecFromSeparated<usize, Pipe>>("1|2|3");
+        assert_normalised::<RowsOfChars<char>>("ab\ncd");
+        assert_normalised::<GridWithMarkers<char, Start>>("S#\n..");
+        assert_normalised::<TwoSections<VecFromLines<usize>, VecFromMultiLines<String>>>(
+            "1\n2\n\nfoo\n\nbar",
+        );
+        assert_normalised::<VecFromMultiLines<String>>("a\nb\n\nc");
+        assert_normalised::<TwoSections<String, String>>("a\nb\n\nc\nd");
+        assert_normalised::<KeyValue<String, usize>>("foo: 42");
+        assert_normalised::<AdjacencyBTreeMap<String, String>>("a: b c\nb: c");
+        assert_normalised::<InclusiveRange<isize>>("-3-5");
+        assert_normalised::<HalfOpenRange<isize>>("x=-3..5");
+        assert_normalised::<Either<usize, String>>("foo");
+        assert_normalised::<Nested<usize>>("[1,[2]]");
+        assert_normalised::<Columns<char>>("[A]    \n[B] [C]\n 1   2 ");
+
+        assert_eq!(
+            VecFromMultiLines::<String>::parse("a\r\nb\r\n\r\nc").unwrap(),
+            vec!["a\nb".to_owned(), "c".to_owned()]
+        );
+        assert_eq!(
+            VecFromMultiLines::<VecFromLines<Str>>::parse_ref("a\r\nb\r\n \r\nc\r\n").unwrap(),
+            vec![vec!["a", "b"], vec!["c"]]
+        );
+    }
 }
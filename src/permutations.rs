@@ -14,7 +14,7 @@ where
     } else {
         (elements, callback) = gen_permutations(k - 1, elements, callback);
         for i in 0..k - 1 {
-            elements.swap(if k % 2 == 0 { i } else { 0 }, k - 1);
+            elements.swap(if k.is_multiple_of(2) { i } else { 0 }, k - 1);
             (elements, callback) = gen_permutations(k - 1, elements, callback);
         }
     }
@@ -1,7 +1,11 @@
 pub use crate::parse::{
-    RowsOfChars, TrimAndParse, TwoSections, VecFromCommaSeparated, VecFromLines, VecFromMultiLines,
+    AdjacencyBTreeMap, AdjacencyHashMap, Arrow, BTreeMapFromLines, BottomUp, Colon, Columns, Comma,
+    Dash, DotDot, Either, Equals, FixedWidth, FooterLabels, GridWithMarkers, HalfOpenRange,
+    HashMapFromLines, InclusiveRange, KeyValue, Marker, MarkerSpec, Nested, OneOf, Owned, Pair,
+    Pipe, RowsOfChars, Semicolon, Space, Str, TopDown, TrimAndParse, TwoSections,
+    VecFromCommaSeparated, VecFromLines, VecFromMultiLines, VecFromSeparated,
     VecFromWhitespaceSeparated,
 };
 pub use crate::scaffold::{solve, solve_part1, solve_part2, Problem};
-pub use crate::{interpret, permutations, tile_map, walk};
+pub use crate::{combinator, interpret, permutations, tile_map, walk};
 pub use anyhow;
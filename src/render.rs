@@ -0,0 +1,227 @@
+use crate::matrix::Matrix;
+use anyhow::{anyhow, Result};
+use std::{
+    fmt::{self, Display},
+    marker::PhantomData,
+};
+
+/// Text rendering of a matrix, one char per cell, created by `Matrix::render`.
+pub struct Render<'a, M: ?Sized, T, F> {
+    matrix: &'a M,
+    tile: F,
+    rulers: bool,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<'a, M, T, F> Render<'a, M, T, F>
+where
+    M: Matrix<T> + ?Sized,
+    F: Fn(&T) -> char,
+{
+    pub fn new(matrix: &'a M, tile: F) -> Self {
+        Self {
+            matrix,
+            tile,
+            rulers: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Prefixes rows with their index and puts column indices (one digit per line) on top.
+    pub fn with_rulers(mut self) -> Self {
+        self.rulers = true;
+        self
+    }
+}
+
+impl<M, T, F> Display for Render<'_, M, T, F>
+where
+    M: Matrix<T> + ?Sized,
+    F: Fn(&T) -> char,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&lines(self.matrix, &self.tile, self.rulers).join("\n"))
+    }
+}
+
+/// Cell by cell comparison of two matrices of the same size, created by `Matrix::diff`.
+///
+/// Displays both matrices side by side followed by a mask marking the differing
+/// cells with `x`, so `assert!(diff.is_empty(), "{}", diff)` pinpoints failures.
+pub struct Diff<'a, L: ?Sized, R: ?Sized, T, F> {
+    left: &'a L,
+    right: &'a R,
+    tile: F,
+    rulers: bool,
+    differences: Vec<(usize, usize)>,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<'a, L, R, T, F> Diff<'a, L, R, T, F>
+where
+    L: Matrix<T> + ?Sized,
+    R: Matrix<T> + ?Sized,
+    T: PartialEq,
+    F: Fn(&T) -> char,
+{
+    pub fn new(left: &'a L, right: &'a R, tile: F) -> Result<Self> {
+        if (left.rows(), left.cols()) != (right.rows(), right.cols()) {
+            return Err(anyhow!(
+                "Cannot diff a {}x{} matrix with a {}x{} matrix",
+                left.rows(),
+                left.cols(),
+                right.rows(),
+                right.cols()
+            ));
+        }
+        let differences = (&left)
+            .iter_indexed()
+            .filter(|&(row, col, value)| value != right.get(row, col))
+            .map(|(row, col, _)| (row, col))
+            .collect();
+        Ok(Self {
+            left,
+            right,
+            tile,
+            rulers: false,
+            differences,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn with_rulers(mut self) -> Self {
+        self.rulers = true;
+        self
+    }
+}
+
+impl<L: ?Sized, R: ?Sized, T, F> Diff<'_, L, R, T, F> {
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /// Positions of the differing cells in row-major order.
+    pub fn differences(&self) -> &[(usize, usize)] {
+        &self.differences
+    }
+}
+
+impl<L, R, T, F> Display for Diff<'_, L, R, T, F>
+where
+    L: Matrix<T> + ?Sized,
+    R: Matrix<T> + ?Sized,
+    F: Fn(&T) -> char,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut mask = vec![vec![false; self.left.cols()]; self.left.rows()];
+        for &(row, col) in &self.differences {
+            mask[row][col] = true;
+        }
+        let left = lines(self.left, &self.tile, self.rulers);
+        let right = lines(self.right, &self.tile, self.rulers);
+        let mask = lines(
+            &mask,
+            &|&differs| if differs { 'x' } else { '.' },
+            self.rulers,
+        );
+        for ((left, right), mask) in left.iter().zip(&right).zip(&mask) {
+            writeln!(f, "{}   {}   {}", left, right, mask)?;
+        }
+        write!(f, "{} cells differ", self.differences.len())
+    }
+}
+
+fn digits(mut value: usize) -> usize {
+    let mut digits = 1;
+    while value >= 10 {
+        value /= 10;
+        digits += 1;
+    }
+    digits
+}
+
+fn lines<M, T, F>(matrix: &M, tile: &F, rulers: bool) -> Vec<String>
+where
+    M: Matrix<T> + ?Sized,
+    F: Fn(&T) -> char,
+{
+    let mut lines = Vec::new();
+    let label_width = digits(matrix.rows().saturating_sub(1));
+    if rulers {
+        let col_digits = digits(matrix.cols().saturating_sub(1));
+        for place in (0..col_digits).rev() {
+            let divisor = 10usize.pow(place as u32);
+            let mut line = " ".repeat(label_width + 1);
+            line.extend((0..matrix.cols()).map(|col| {
+                if col < divisor && place > 0 {
+                    ' '
+                } else {
+                    char::from_digit((col / divisor % 10) as u32, 10).unwrap()
+                }
+            }));
+            lines.push(line);
+        }
+    }
+    for row in 0..matrix.rows() {
+        let mut line = if rulers {
+            format!("{:>width$} ", row, width = label_width)
+        } else {
+            String::new()
+        };
+        line.extend((&matrix).iter_row(row).map(tile));
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{grid::Grid, tile_map::TileMap};
+
+    #[test]
+    fn test_render() {
+        let m = vec![vec![true, false, true], vec![false, true, false]];
+        let tile = |&on: &bool| if on { '#' } else { '.' };
+        assert_eq!(m.render(tile).to_string(), "#.#\n.#.");
+        assert_eq!((&m).rotate().render(tile).to_string(), "#.\n.#\n#.");
+        assert_eq!(
+            m.render(tile).with_rulers().to_string(),
+            "  012\n0 #.#\n1 .#."
+        );
+    }
+
+    #[test]
+    fn test_rulers_with_wide_matrix() {
+        let m = vec![vec!['.'; 12]; 11];
+        let rendered = m.render(|&c| c).with_rulers().to_string();
+        let lines = rendered.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "             11");
+        assert_eq!(lines[1], "   012345678901");
+        assert_eq!(lines[2], " 0 ............");
+        assert_eq!(lines[12], "10 ............");
+    }
+
+    #[test]
+    fn test_display() {
+        let rows = vec![vec!['#', '.'], vec!['.', '#']];
+        assert_eq!(TileMap::from(&rows).to_string(), "#.\n.#");
+        assert_eq!(Grid::try_from(rows).unwrap().to_string(), "#.\n.#");
+    }
+
+    #[test]
+    fn test_diff() {
+        let left = vec![vec!['a', 'b'], vec!['c', 'd']];
+        let right = vec![vec!['a', 'b'], vec!['c', 'e']];
+        let diff = left.diff(&right, |&c| c).unwrap();
+        assert!(!diff.is_empty());
+        assert_eq!(diff.differences(), &[(1, 1)]);
+        assert_eq!(
+            diff.to_string(),
+            "ab   ab   ..\ncd   ce   .x\n1 cells differ"
+        );
+
+        assert!(left.diff(&left, |&c| c).unwrap().is_empty());
+        assert!(left.diff(&vec![vec!['a']], |&c| c).is_err());
+    }
+}
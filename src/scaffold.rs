@@ -1,26 +1,67 @@
-pub use crate::parse::Parse;
+pub use crate::parse::{Parse, ParseRef};
 
-pub trait Problem {
-    type Input: Parse;
+/// Input is parsed with `ParseRef` so `Parsed` may borrow from the raw input
+/// for `'a`. The built-in combinators implement both traits; wrap a hand-written
+/// `Parse` implementation in `parse::Owned`, e.g. `VecFromLines<Owned<Custom>>`.
+pub trait Problem<'a> {
+    type Input: ParseRef<'a>;
     type Part1: std::fmt::Display;
     type Part2: std::fmt::Display;
 
-    fn solve_part1(input: &<Self::Input as Parse>::Parsed) -> Self::Part1;
-    fn solve_part2(input: &<Self::Input as Parse>::Parsed) -> Self::Part2;
+    fn solve_part1(input: &<Self::Input as ParseRef<'a>>::Parsed) -> Self::Part1;
+    fn solve_part2(input: &<Self::Input as ParseRef<'a>>::Parsed) -> Self::Part2;
 }
 
-pub fn solve_part1<P: Problem>(raw_input: &str) -> P::Part1 {
-    let input = P::Input::parse(raw_input).expect("input should parse");
+pub fn solve_part1<'a, P: Problem<'a>>(raw_input: &'a str) -> P::Part1 {
+    let input = P::Input::parse_ref(raw_input).expect("input should parse");
     P::solve_part1(&input)
 }
 
-pub fn solve_part2<P: Problem>(raw_input: &str) -> P::Part2 {
-    let input = P::Input::parse(raw_input).expect("input should parse");
+pub fn solve_part2<'a, P: Problem<'a>>(raw_input: &'a str) -> P::Part2 {
+    let input = P::Input::parse_ref(raw_input).expect("input should parse");
     P::solve_part2(&input)
 }
 
-pub fn solve<P: Problem>(raw_input: &str) {
-    let input = P::Input::parse(raw_input).expect("input should parse");
+pub fn solve<'a, P: Problem<'a>>(raw_input: &'a str) {
+    let input = P::Input::parse_ref(raw_input).expect("input should parse");
     println!("Part 1: {}", P::solve_part1(&input));
     println!("Part 2: {}", P::solve_part2(&input));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{Owned, VecFromLines};
+
+    struct Range;
+    impl Parse for Range {
+        type Parsed = (usize, usize);
+        fn parse(raw_input: &str) -> anyhow::Result<Self::Parsed> {
+            let (start, end) = raw_input
+                .split_once('-')
+                .ok_or_else(|| anyhow::anyhow!("Expected a range"))?;
+            Ok((usize::parse(start)?, usize::parse(end)?))
+        }
+    }
+
+    struct Day;
+    impl Problem<'_> for Day {
+        type Input = VecFromLines<Owned<Range>>;
+        type Part1 = usize;
+        type Part2 = usize;
+
+        fn solve_part1(input: &Vec<(usize, usize)>) -> usize {
+            input.iter().map(|(start, end)| end - start).sum()
+        }
+
+        fn solve_part2(input: &Vec<(usize, usize)>) -> usize {
+            input.len()
+        }
+    }
+
+    #[test]
+    fn test_custom_parse_input() {
+        assert_eq!(solve_part1::<Day>("1-4\n2-3"), 4);
+        assert_eq!(solve_part2::<Day>("1-4\n2-3"), 2);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{grid::Grid, tile_map::TileMap};
+use std::collections::HashMap;
+
+/// Inclusive range of rows and columns holding set cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bounds {
+    pub min_row: isize,
+    pub max_row: isize,
+    pub min_col: isize,
+    pub max_col: isize,
+}
+
+impl Bounds {
+    fn point(row: isize, col: isize) -> Self {
+        Self {
+            min_row: row,
+            max_row: row,
+            min_col: col,
+            max_col: col,
+        }
+    }
+
+    fn include(&mut self, row: isize, col: isize) {
+        self.min_row = self.min_row.min(row);
+        self.max_row = self.max_row.max(row);
+        self.min_col = self.min_col.min(col);
+        self.max_col = self.max_col.max(col);
+    }
+
+    pub fn rows(&self) -> usize {
+        (self.max_row - self.min_row + 1) as usize
+    }
+
+    pub fn cols(&self) -> usize {
+        (self.max_col - self.min_col + 1) as usize
+    }
+
+    pub fn contains(&self, row: isize, col: isize) -> bool {
+        (self.min_row..=self.max_row).contains(&row) && (self.min_col..=self.max_col).contains(&col)
+    }
+}
+
+/// Unbounded plane of tiles stored in a `HashMap`, where unset cells read as `default`.
+///
+/// Uses the same signed `(row, col)` coordinates as `TileMap`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T: Copy> {
+    cells: HashMap<(isize, isize), T>,
+    default: T,
+    bounds: Option<Bounds>,
+}
+
+impl<T: Copy> SparseGrid<T> {
+    pub fn new(default: T) -> Self {
+        Self {
+            cells: HashMap::new(),
+            default,
+            bounds: None,
+        }
+    }
+
+    pub fn get(&self, row: isize, col: isize) -> T {
+        self.cells.get(&(row, col)).copied().unwrap_or(self.default)
+    }
+
+    /// Sets a cell, growing the bounds to include it. Returns the previous tile if set.
+    pub fn set(&mut self, row: isize, col: isize, tile: T) -> Option<T> {
+        match &mut self.bounds {
+            Some(bounds) => bounds.include(row, col),
+            None => self.bounds = Some(Bounds::point(row, col)),
+        }
+        self.cells.insert((row, col), tile)
+    }
+
+    /// Unsets a cell. The bounds don't shrink until `shrink_bounds` is called.
+    pub fn remove(&mut self, row: isize, col: isize) -> Option<T> {
+        self.cells.remove(&(row, col))
+    }
+
+    pub fn is_set(&self, row: isize, col: isize) -> bool {
+        self.cells.contains_key(&(row, col))
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn default_tile(&self) -> T {
+        self.default
+    }
+
+    /// Smallest rectangle containing every cell set so far, `None` if nothing was set.
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    /// Recomputes the bounds from the cells that are still set.
+    pub fn shrink_bounds(&mut self) {
+        self.bounds = None;
+        for &(row, col) in self.cells.keys() {
+            match &mut self.bounds {
+                Some(bounds) => bounds.include(row, col),
+                None => self.bounds = Some(Bounds::point(row, col)),
+            }
+        }
+    }
+
+    /// Set cells in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = ((isize, isize), T)> + '_ {
+        self.cells.iter().map(|(&position, &tile)| (position, tile))
+    }
+
+    /// Same layout as `TileMap::get_surroundings`, for use with `tile_map::iter_all_neighbours`
+    /// and friends. Every entry is `Some` as the plane has no edge.
+    pub fn get_surroundings(&self, row: isize, col: isize) -> [[Option<T>; 3]; 3] {
+        [-1, 0, 1].map(|d_row| [-1, 0, 1].map(|d_col| Some(self.get(row + d_row, col + d_col))))
+    }
+
+    /// Dense copy of the bounding box, with `bounds().min_row`/`min_col` at `(0, 0)`.
+    pub fn to_grid(&self) -> Grid<T> {
+        let Some(bounds) = self.bounds else {
+            return Grid::new(0, 0, self.default);
+        };
+        let cells = (bounds.min_row..=bounds.max_row)
+            .flat_map(|row| (bounds.min_col..=bounds.max_col).map(move |col| self.get(row, col)))
+            .collect();
+        Grid::from_cells(bounds.cols(), cells).unwrap()
+    }
+
+    pub fn to_tile_map(&self) -> TileMap<T> {
+        TileMap::from(self.to_grid())
+    }
+}
+
+impl<T: Copy> Extend<((isize, isize), T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = ((isize, isize), T)>>(&mut self, cells: I) {
+        for ((row, col), tile) in cells {
+            self.set(row, col, tile);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{matrix::Matrix, tile_map::iter_all_neighbours};
+
+    #[test]
+    fn test_set_and_bounds() {
+        let mut grid = SparseGrid::new('.');
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.get(100, -100), '.');
+
+        grid.set(2, 3, '#');
+        grid.extend([((-1, 0), '#'), ((0, 5), 'o')]);
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.get(-1, 0), '#');
+        assert!(!grid.is_set(0, 0));
+        let bounds = grid.bounds().unwrap();
+        assert_eq!(
+            bounds,
+            Bounds {
+                min_row: -1,
+                max_row: 2,
+                min_col: 0,
+                max_col: 5,
+            }
+        );
+        assert_eq!((bounds.rows(), bounds.cols()), (4, 6));
+
+        assert_eq!(grid.remove(0, 5), Some('o'));
+        assert_eq!(grid.bounds(), Some(bounds));
+        grid.shrink_bounds();
+        assert_eq!(grid.bounds().unwrap().max_col, 3);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let mut grid = SparseGrid::new(false);
+        grid.extend([((0, 0), true), ((-1, -1), true), ((1, 0), true)]);
+        let lit = iter_all_neighbours(grid.get_surroundings(0, 0))
+            .filter(|&on| on)
+            .count();
+        assert_eq!(lit, 2);
+        assert_eq!(grid.get_surroundings(0, 0)[1][1], Some(true));
+    }
+
+    #[test]
+    fn test_to_grid() {
+        let mut grid = SparseGrid::new('.');
+        grid.extend([((-2, 1), '#'), ((-1, 3), '#')]);
+        let dense = grid.to_grid();
+        assert_eq!(dense.to_string(), "#..\n..#");
+        assert_eq!(grid.to_tile_map().get(1, 2), Some('#'));
+        assert_eq!(
+            dense.render(|&c| c).with_rulers().to_string(),
+            "  012\n0 #..\n1 ..#"
+        );
+        assert_eq!(SparseGrid::new(0).to_grid().rows(), 0);
+    }
+}
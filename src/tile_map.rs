@@ -1,4 +1,7 @@
-#[derive(Clone, Hash, PartialEq, Eq)]
+use crate::matrix::{Matrix, MatrixMut};
+use std::fmt::{self, Display};
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct TileMap<T: Copy> {
     map: Vec<Vec<T>>,
 }
@@ -15,6 +18,46 @@ impl<T: Copy> From<Vec<Vec<T>>> for TileMap<T> {
     }
 }
 
+impl<T: Copy> From<TileMap<T>> for Vec<Vec<T>> {
+    fn from(tile_map: TileMap<T>) -> Self {
+        tile_map.map
+    }
+}
+
+impl<T: Copy> Matrix<T> for TileMap<T> {
+    fn get(&self, row: usize, col: usize) -> &T {
+        self.map.get(row, col)
+    }
+
+    fn rows(&self) -> usize {
+        self.map.rows()
+    }
+
+    fn cols(&self) -> usize {
+        self.map.cols()
+    }
+
+    fn try_get(&self, row: usize, col: usize) -> Option<&T> {
+        self.map.try_get(row, col)
+    }
+}
+
+impl<T: Copy> MatrixMut<T> for TileMap<T> {
+    fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+        self.map.get_mut(row, col)
+    }
+
+    fn try_get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.map.try_get_mut(row, col)
+    }
+}
+
+impl Display for TileMap<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(|&tile| tile).fmt(f)
+    }
+}
+
 impl<T: Copy> TileMap<T> {
     pub fn get(&self, row: isize, col: isize) -> Option<T> {
         if let (Ok(row), Ok(col)) = (usize::try_from(row), usize::try_from(col)) {
@@ -92,8 +135,8 @@ pub fn iter_all<T: Copy>(zone: [[Option<T>; 3]; 3]) -> impl Iterator<Item = T> {
 pub fn iter_all_neighbours<T: Copy>(zone: [[Option<T>; 3]; 3]) -> impl Iterator<Item = T> {
     zone[0]
         .into_iter()
-        .chain([zone[1][0], zone[1][2]].into_iter())
-        .chain(zone[2].into_iter())
+        .chain([zone[1][0], zone[1][2]])
+        .chain(zone[2])
         .flatten()
 }
 